@@ -1,5 +1,7 @@
 use crate::error::{Result, WhisperError};
 use crate::log_info;
+use std::io::Write;
+use std::process::Stdio;
 use tempfile::NamedTempFile;
 
 pub fn check_ffmpeg_available() -> Result<()> {
@@ -13,30 +15,53 @@ pub fn check_ffmpeg_available() -> Result<()> {
     }
 }
 
-pub fn convert_to_wav(input_path: &str) -> Result<NamedTempFile> {
+pub fn convert_to_wav(input_path: &str, threads: usize) -> Result<NamedTempFile> {
     check_ffmpeg_available()?;
 
-    let output = NamedTempFile::with_suffix(".wav")?;
-
     log_info!("Converting {} to WAV...", input_path);
+    run_ffmpeg(input_path, None, threads)
+}
+
+/// Converts in-memory audio by piping it into ffmpeg's stdin.
+pub fn convert_bytes_to_wav(data: &[u8], threads: usize) -> Result<NamedTempFile> {
+    check_ffmpeg_available()?;
+
+    log_info!("Converting input stream to WAV...");
+    run_ffmpeg("pipe:0", Some(data), threads)
+}
 
-    let result = std::process::Command::new("ffmpeg")
+fn run_ffmpeg(input: &str, stdin_data: Option<&[u8]>, threads: usize) -> Result<NamedTempFile> {
+    let output = NamedTempFile::with_suffix(".wav")?;
+
+    let child = std::process::Command::new("ffmpeg")
+        .arg("-threads")
+        .arg(threads.to_string())
         .arg("-i")
-        .arg(input_path)
+        .arg(input)
         .arg("-acodec")
         .arg("pcm_s16le")
         .arg("-ar")
         .arg("16000")
-        .arg("-ac")
-        .arg("1")
         .arg("-y")
         .arg(output.path())
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .output();
+        .stdin(if stdin_data.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
 
-    match result {
-        Ok(output_status) if output_status.status.success() => {
+    let mut child = child.map_err(|_| WhisperError::AudioConversionFailed)?;
+
+    if let (Some(data), Some(mut stdin)) = (stdin_data, child.stdin.take()) {
+        // ffmpeg may stop reading early on bad input; the exit status reports that
+        let _ = stdin.write_all(data);
+    }
+
+    match child.wait() {
+        Ok(status) if status.success() => {
             log_info!("Conversion complete");
             Ok(output)
         }
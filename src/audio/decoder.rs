@@ -0,0 +1,185 @@
+use crate::audio::formats::AudioFormat;
+use crate::audio::loader::DecodedAudio;
+use crate::error::{Result, WhisperError};
+use std::fs::File;
+use std::io::{Cursor, ErrorKind};
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Decodes a file whose container was identified as `format`; the format is
+/// passed to the probe as a hint since the extension may be missing or wrong.
+pub fn decode_file(path: &Path, format: AudioFormat) -> Result<DecodedAudio> {
+    let file = File::open(path).map_err(|_| WhisperError::AudioLoadFailed)?;
+
+    let mut hint = Hint::new();
+    hint.with_extension(format.extension());
+
+    decode_source(Box::new(file), &hint)
+}
+
+pub fn decode_bytes(data: Vec<u8>, format: AudioFormat) -> Result<DecodedAudio> {
+    let mut hint = Hint::new();
+    hint.with_extension(format.extension());
+
+    decode_source(Box::new(Cursor::new(data)), &hint)
+}
+
+pub fn decode_source(source: Box<dyn MediaSource>, hint: &Hint) -> Result<DecodedAudio> {
+    let stream = MediaSourceStream::new(source, Default::default());
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|_| WhisperError::UnsupportedFileType)?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or(WhisperError::AudioLoadFailed)?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate;
+    let mut channels = track.codec_params.channels.map(|c| c.count() as u16);
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|_| WhisperError::UnsupportedFileType)?;
+
+    let mut samples = Vec::new();
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(_) => return Err(WhisperError::AudioLoadFailed),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // a corrupt frame is skipped rather than failing the whole file
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(_) => return Err(WhisperError::AudioLoadFailed),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate = Some(spec.rate);
+        channels = Some(spec.channels.count() as u16);
+
+        let required = decoded.capacity() * spec.channels.count();
+        if buffer.as_ref().is_none_or(|b| b.capacity() < required) {
+            buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+        }
+        if let Some(buffer) = buffer.as_mut() {
+            buffer.copy_interleaved_ref(decoded);
+            samples.extend_from_slice(buffer.samples());
+        }
+    }
+
+    match (sample_rate, channels) {
+        (Some(sample_rate), Some(channels)) if !samples.is_empty() => Ok(DecodedAudio {
+            samples,
+            sample_rate,
+            channels,
+        }),
+        _ => Err(WhisperError::AudioLoadFailed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::loader::{load_audio, SAMPLE_RATE};
+    use tempfile::NamedTempFile;
+
+    // About 300 ms each: a 440 Hz tone in 44.1 kHz stereo FLAC, and 52 short
+    // Vorbis blocks of a single ~900 Hz partial in 22.05 kHz stereo.
+    const FLAC_TONE: &[u8] = include_bytes!("../../tests/fixtures/tone.flac");
+    const VORBIS_TONE: &[u8] = include_bytes!("../../tests/fixtures/tone.ogg");
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0, |peak, s| peak.max(s.abs()))
+    }
+
+    #[test]
+    fn test_decode_bytes_flac() {
+        let decoded = decode_bytes(FLAC_TONE.to_vec(), AudioFormat::Flac).unwrap();
+        assert_eq!(decoded.sample_rate, 44100);
+        assert_eq!(decoded.channels, 2);
+        assert_eq!(decoded.samples.len(), 13230 * 2);
+        assert!((peak(&decoded.samples) - 0.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_decode_bytes_vorbis() {
+        let decoded = decode_bytes(VORBIS_TONE.to_vec(), AudioFormat::Ogg).unwrap();
+        assert_eq!(decoded.sample_rate, 22050);
+        assert_eq!(decoded.channels, 2);
+        assert_eq!(decoded.samples.len(), 6656 * 2);
+        assert!(peak(&decoded.samples) > 0.0);
+    }
+
+    #[test]
+    fn test_load_audio_flac_resamples_to_mono() {
+        let audio = load_audio(&fixture("tone.flac")).unwrap();
+        assert_eq!(audio.sample_rate, SAMPLE_RATE);
+        assert_eq!(audio.samples.len(), 4800);
+        // the right channel is at half level, so the downmix peaks at 3/4
+        let peak = peak(&audio.samples[1000..4000]);
+        assert!((peak - 0.1875).abs() < 0.01, "peak {}", peak);
+    }
+
+    #[test]
+    fn test_load_audio_vorbis_resamples_to_mono() {
+        let audio = load_audio(&fixture("tone.ogg")).unwrap();
+        assert_eq!(audio.sample_rate, SAMPLE_RATE);
+        // 6656 frames at 22.05 kHz
+        assert_eq!(audio.samples.len(), 4830);
+        assert!(peak(&audio.samples) > 0.0);
+    }
+
+    #[test]
+    fn test_decode_file_not_found() {
+        let result = decode_file(Path::new("/nonexistent/file.mp3"), AudioFormat::Mp3);
+        assert!(matches!(result, Err(WhisperError::AudioLoadFailed)));
+    }
+
+    #[test]
+    fn test_decode_file_garbage() {
+        let temp_file = NamedTempFile::with_suffix(".mp3").unwrap();
+        std::fs::write(temp_file.path(), b"definitely not audio").unwrap();
+        assert!(decode_file(temp_file.path(), AudioFormat::Mp3).is_err());
+    }
+
+    #[test]
+    fn test_decode_bytes_garbage() {
+        let result = decode_bytes(b"fLaC but not really".to_vec(), AudioFormat::Flac);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_decode_source_empty() {
+        let source = Box::new(std::io::Cursor::new(Vec::<u8>::new()));
+        assert!(decode_source(source, &Hint::new()).is_err());
+    }
+}
@@ -1,3 +1,9 @@
+use std::fs::File;
+use std::io::Read;
+
+/// Number of leading bytes inspected when sniffing a file's format.
+pub const SNIFF_LEN: usize = 64;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AudioFormat {
     Wav,
@@ -27,14 +33,191 @@ impl AudioFormat {
         }
     }
 
+    /// Identifies the container from its leading bytes.
+    pub fn from_magic(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => {
+                Some(AudioFormat::Wav)
+            }
+            [b'f', b'L', b'a', b'C', ..] => Some(AudioFormat::Flac),
+            [b'O', b'g', b'g', b'S', ..] => Some(AudioFormat::Ogg),
+            [0x1A, 0x45, 0xDF, 0xA3, ..] => Some(AudioFormat::Webm),
+            [b'I', b'D', b'3', ..] => Some(AudioFormat::Mp3),
+            [_, _, _, _, b'f', b't', b'y', b'p', brand @ ..] => {
+                if brand.starts_with(b"M4A ") || brand.starts_with(b"M4B ") {
+                    Some(AudioFormat::M4a)
+                } else {
+                    Some(AudioFormat::Mp4)
+                }
+            }
+            // ADTS frames sync like MPEG audio but always carry layer 0
+            [0xFF, second, ..] if second & 0xF6 == 0xF0 => Some(AudioFormat::Aac),
+            [0xFF, second, ..] if second & 0xE0 == 0xE0 && second & 0x06 != 0 => {
+                Some(AudioFormat::Mp3)
+            }
+            _ => None,
+        }
+    }
+
+    /// Detects the format from the file contents, using the extension only
+    /// when the contents are not recognized (or cannot be read).
+    pub fn detect(path: &str) -> Option<Self> {
+        let mut header = Vec::with_capacity(SNIFF_LEN);
+        let sniffed = File::open(path)
+            .and_then(|file| file.take(SNIFF_LEN as u64).read_to_end(&mut header))
+            .ok()
+            .and_then(|_| Self::from_magic(&header));
+
+        sniffed.or_else(|| Self::from_path(path))
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioFormat::Wav => "wav",
+            AudioFormat::Webm => "webm",
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::M4a => "m4a",
+            AudioFormat::Mp4 => "mp4",
+            AudioFormat::Ogg => "ogg",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Aac => "aac",
+        }
+    }
+
     pub fn needs_conversion(&self) -> bool {
         !matches!(self, AudioFormat::Wav)
     }
+
+    /// Whether the built-in decoders handle this format without ffmpeg.
+    pub fn supports_native_decoding(&self) -> bool {
+        cfg!(feature = "native-decoding")
+            && matches!(
+                self,
+                AudioFormat::Mp3
+                    | AudioFormat::M4a
+                    | AudioFormat::Mp4
+                    | AudioFormat::Ogg
+                    | AudioFormat::Flac
+                    | AudioFormat::Aac
+            )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::NamedTempFile;
+
+    fn temp_file_with(suffix: &str, contents: &[u8]) -> NamedTempFile {
+        let temp_file = NamedTempFile::with_suffix(suffix).unwrap();
+        std::fs::write(temp_file.path(), contents).unwrap();
+        temp_file
+    }
+
+    #[test]
+    fn test_from_magic() {
+        assert_eq!(
+            AudioFormat::from_magic(b"RIFF\x24\x00\x00\x00WAVEfmt "),
+            Some(AudioFormat::Wav)
+        );
+        assert_eq!(
+            AudioFormat::from_magic(b"fLaC\x00"),
+            Some(AudioFormat::Flac)
+        );
+        assert_eq!(
+            AudioFormat::from_magic(b"OggS\x00\x02"),
+            Some(AudioFormat::Ogg)
+        );
+        assert_eq!(
+            AudioFormat::from_magic(&[0x1A, 0x45, 0xDF, 0xA3, 0x9F]),
+            Some(AudioFormat::Webm)
+        );
+        assert_eq!(
+            AudioFormat::from_magic(b"ID3\x04\x00"),
+            Some(AudioFormat::Mp3)
+        );
+        assert_eq!(
+            AudioFormat::from_magic(&[0xFF, 0xFB, 0x90, 0x64]),
+            Some(AudioFormat::Mp3)
+        );
+        assert_eq!(
+            AudioFormat::from_magic(&[0xFF, 0xF1, 0x50, 0x80]),
+            Some(AudioFormat::Aac)
+        );
+        assert_eq!(
+            AudioFormat::from_magic(b"\x00\x00\x00\x20ftypM4A \x00\x00"),
+            Some(AudioFormat::M4a)
+        );
+        assert_eq!(
+            AudioFormat::from_magic(b"\x00\x00\x00\x18ftypisom\x00\x00"),
+            Some(AudioFormat::Mp4)
+        );
+    }
+
+    #[test]
+    fn test_from_magic_unknown() {
+        assert_eq!(AudioFormat::from_magic(b""), None);
+        assert_eq!(AudioFormat::from_magic(b"not an audio file"), None);
+        assert_eq!(AudioFormat::from_magic(b"RIFF\x00\x00\x00\x00AVI "), None);
+        assert_eq!(AudioFormat::from_magic(&[0xFF, 0x00]), None);
+    }
+
+    #[test]
+    fn test_detect_prefers_content_over_extension() {
+        let file = temp_file_with(".mp3", b"RIFF\x24\x00\x00\x00WAVEfmt ");
+        assert_eq!(
+            AudioFormat::detect(&file.path().to_string_lossy()),
+            Some(AudioFormat::Wav)
+        );
+    }
+
+    #[test]
+    fn test_detect_without_extension() {
+        let file = temp_file_with("", b"fLaC\x00\x00\x00\x22");
+        assert_eq!(
+            AudioFormat::detect(&file.path().to_string_lossy()),
+            Some(AudioFormat::Flac)
+        );
+
+        let file = temp_file_with(".bin", b"OggS\x00\x02");
+        assert_eq!(
+            AudioFormat::detect(&file.path().to_string_lossy()),
+            Some(AudioFormat::Ogg)
+        );
+    }
+
+    #[test]
+    fn test_detect_falls_back_to_extension() {
+        let file = temp_file_with(".flac", b"");
+        assert_eq!(
+            AudioFormat::detect(&file.path().to_string_lossy()),
+            Some(AudioFormat::Flac)
+        );
+        assert_eq!(
+            AudioFormat::detect("/nonexistent/file.wav"),
+            Some(AudioFormat::Wav)
+        );
+
+        let file = temp_file_with(".txt", b"not an audio file");
+        assert_eq!(AudioFormat::detect(&file.path().to_string_lossy()), None);
+    }
+
+    #[test]
+    fn test_extension_round_trip() {
+        for format in [
+            AudioFormat::Wav,
+            AudioFormat::Webm,
+            AudioFormat::Mp3,
+            AudioFormat::M4a,
+            AudioFormat::Mp4,
+            AudioFormat::Ogg,
+            AudioFormat::Flac,
+            AudioFormat::Aac,
+        ] {
+            let path = format!("audio.{}", format.extension());
+            assert_eq!(AudioFormat::from_path(&path), Some(format));
+        }
+    }
 
     #[test]
     fn test_format_detection_wav() {
@@ -104,4 +287,24 @@ mod tests {
         assert!(AudioFormat::Flac.needs_conversion());
         assert!(AudioFormat::Aac.needs_conversion());
     }
+
+    #[test]
+    #[cfg(feature = "native-decoding")]
+    fn test_supports_native_decoding() {
+        assert!(AudioFormat::Mp3.supports_native_decoding());
+        assert!(AudioFormat::M4a.supports_native_decoding());
+        assert!(AudioFormat::Mp4.supports_native_decoding());
+        assert!(AudioFormat::Ogg.supports_native_decoding());
+        assert!(AudioFormat::Flac.supports_native_decoding());
+        assert!(AudioFormat::Aac.supports_native_decoding());
+        assert!(!AudioFormat::Webm.supports_native_decoding());
+        assert!(!AudioFormat::Wav.supports_native_decoding());
+    }
+
+    #[test]
+    #[cfg(not(feature = "native-decoding"))]
+    fn test_supports_native_decoding_disabled() {
+        assert!(!AudioFormat::Mp3.supports_native_decoding());
+        assert!(!AudioFormat::Flac.supports_native_decoding());
+    }
 }
@@ -1,65 +1,287 @@
-use crate::audio::converter::convert_to_wav;
+use crate::audio::converter::{convert_bytes_to_wav, convert_to_wav};
+#[cfg(feature = "native-decoding")]
+use crate::audio::decoder::{decode_bytes, decode_file};
 use crate::audio::formats::AudioFormat;
+use crate::audio::preprocess::{preprocess, PreprocessOptions};
+use crate::audio::raw::{decode_raw_pcm, RawFormat};
+use crate::audio::resample::{ResampleQuality, StreamResampler, CHUNK_FRAMES};
+use crate::audio::wav::decode_wav;
 use crate::error::{Result, WhisperError};
 use crate::log_info;
+use crate::log_warning;
 use hound::WavReader;
-use rubato::{Resampler, SincFixedIn};
+use std::io::{Cursor, Read};
 use std::path::Path;
 
 pub const SAMPLE_RATE: u32 = 16000;
 
+/// File name that reads the audio from standard input instead.
+pub const STDIN_PATH: &str = "-";
+
+/// Interleaved samples as decoded, before downmixing and resampling.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
 pub struct AudioData {
     pub samples: Vec<f32>,
     pub sample_rate: u32,
 }
 
+/// How multichannel audio is reduced to the mono signal whisper expects.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ChannelMix {
+    /// Average the channels, weighting 5.1 audio towards the front speakers.
+    #[default]
+    Downmix,
+    /// Weighted average with one weight per channel.
+    Weighted(Vec<f32>),
+    /// A single channel, counted from zero.
+    Select(u16),
+}
+
+// L, R, C, LFE, Ls, Rs: surrounds at -3 dB and the LFE dropped
+const SURROUND_51_WEIGHTS: [f32; 6] = [1.0, 1.0, 0.707, 0.0, 0.707, 0.707];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadOptions {
+    pub threads: usize,
+    pub raw: Option<RawFormat>,
+    pub channels: ChannelMix,
+    pub resample_quality: ResampleQuality,
+    pub preprocess: PreprocessOptions,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            threads: 1,
+            raw: None,
+            channels: ChannelMix::default(),
+            resample_quality: ResampleQuality::default(),
+            preprocess: PreprocessOptions::default(),
+        }
+    }
+}
+
 pub fn load_audio(file_path: &str) -> Result<AudioData> {
-    let format = AudioFormat::from_path(file_path).ok_or(WhisperError::UnsupportedFileType)?;
+    load_audio_with_options(file_path, &LoadOptions::default())
+}
+
+pub fn load_audio_with_options(file_path: &str, options: &LoadOptions) -> Result<AudioData> {
+    let decoded = decode_input(file_path, options)?;
+    finish_audio(&decoded, &options.channels, options)
+}
+
+/// Loads every channel as its own mono track, e.g. to transcribe each side
+/// of a call recording separately.
+pub fn load_audio_channels(file_path: &str, options: &LoadOptions) -> Result<Vec<AudioData>> {
+    let decoded = decode_input(file_path, options)?;
+    (0..decoded.channels)
+        .map(|channel| finish_audio(&decoded, &ChannelMix::Select(channel), options))
+        .collect()
+}
+
+fn decode_input(file_path: &str, options: &LoadOptions) -> Result<DecodedAudio> {
+    if let Some(raw) = &options.raw {
+        let data = if file_path == STDIN_PATH {
+            read_stdin()?
+        } else {
+            std::fs::read(file_path).map_err(|_| WhisperError::AudioLoadFailed)?
+        };
+        return decode_raw(&data, raw);
+    }
+
+    if file_path == STDIN_PATH {
+        return decode_input_bytes(read_stdin()?, options);
+    }
+
+    let format = AudioFormat::detect(file_path).ok_or(WhisperError::UnsupportedFileType)?;
+
+    if format.supports_native_decoding() {
+        match decode_native(file_path, format) {
+            Ok(decoded) => return Ok(decoded),
+            Err(_) => {
+                log_warning!("Native decoding failed, falling back to ffmpeg");
+            }
+        }
+    }
 
-    let samples = if format.needs_conversion() {
-        let temp_wav = convert_to_wav(file_path)?;
-        load_wav(temp_wav.path())?
+    if format.needs_conversion() {
+        let temp_wav = convert_to_wav(file_path, options.threads)?;
+        load_wav(temp_wav.path())
     } else {
-        load_wav(Path::new(file_path))?
-    };
+        load_wav(Path::new(file_path))
+    }
+}
+
+fn read_stdin() -> Result<Vec<u8>> {
+    log_info!("Reading audio from stdin...");
+    let mut data = Vec::new();
+    std::io::stdin().lock().read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Loads headerless PCM whose layout is given by `format`.
+pub fn load_raw_pcm(data: &[u8], format: &RawFormat, options: &LoadOptions) -> Result<AudioData> {
+    finish_audio(&decode_raw(data, format)?, &options.channels, options)
+}
+
+fn decode_raw(data: &[u8], format: &RawFormat) -> Result<DecodedAudio> {
+    Ok(DecodedAudio {
+        samples: decode_raw_pcm(data, format)?,
+        sample_rate: format.sample_rate,
+        channels: format.channels,
+    })
+}
+
+/// Loads audio held in memory, e.g. read from a pipe. The format is taken
+/// from the magic bytes since there is no file name to go by.
+pub fn load_audio_from_bytes(data: Vec<u8>, options: &LoadOptions) -> Result<AudioData> {
+    let decoded = decode_input_bytes(data, options)?;
+    finish_audio(&decoded, &options.channels, options)
+}
+
+fn decode_input_bytes(data: Vec<u8>, options: &LoadOptions) -> Result<DecodedAudio> {
+    let format = AudioFormat::from_magic(&data).ok_or(WhisperError::UnsupportedFileType)?;
+
+    if !format.needs_conversion() {
+        return match WavReader::new(Cursor::new(&data[..])) {
+            Ok(reader) => read_wav(reader),
+            Err(_) => load_wav_bytes(&data),
+        };
+    }
+
+    if format.supports_native_decoding() {
+        match decode_native_bytes(&data, format) {
+            Ok(decoded) => return Ok(decoded),
+            Err(_) => {
+                log_warning!("Native decoding failed, falling back to ffmpeg");
+            }
+        }
+    }
+
+    let temp_wav = convert_bytes_to_wav(&data, options.threads)?;
+    load_wav(temp_wav.path())
+}
+
+#[cfg(feature = "native-decoding")]
+fn decode_native_bytes(data: &[u8], format: AudioFormat) -> Result<DecodedAudio> {
+    decode_bytes(data.to_vec(), format)
+}
+
+#[cfg(not(feature = "native-decoding"))]
+fn decode_native_bytes(_data: &[u8], _format: AudioFormat) -> Result<DecodedAudio> {
+    Err(WhisperError::UnsupportedFileType)
+}
+
+#[cfg(feature = "native-decoding")]
+fn decode_native(file_path: &str, format: AudioFormat) -> Result<DecodedAudio> {
+    log_info!("Decoding {}...", file_path);
+    decode_file(Path::new(file_path), format)
+}
+
+#[cfg(not(feature = "native-decoding"))]
+fn decode_native(_file_path: &str, _format: AudioFormat) -> Result<DecodedAudio> {
+    Err(WhisperError::UnsupportedFileType)
+}
 
-    Ok(samples)
+fn load_wav(path: &Path) -> Result<DecodedAudio> {
+    match WavReader::open(path) {
+        Ok(reader) => read_wav(reader),
+        // hound rejects 64-bit float and G.711, which our own parser handles
+        Err(hound::Error::Unsupported | hound::Error::FormatError(_)) => {
+            load_wav_bytes(&std::fs::read(path)?)
+        }
+        Err(_) => Err(WhisperError::AudioLoadFailed),
+    }
 }
 
-fn load_wav(path: &Path) -> Result<AudioData> {
-    let reader = WavReader::open(path).map_err(|_| WhisperError::AudioLoadFailed)?;
+fn load_wav_bytes(data: &[u8]) -> Result<DecodedAudio> {
+    let (header, samples) = decode_wav(data)?;
+    Ok(DecodedAudio {
+        samples,
+        sample_rate: header.sample_rate,
+        channels: header.channels,
+    })
+}
+
+fn read_wav<R: Read>(reader: WavReader<R>) -> Result<DecodedAudio> {
     let spec = reader.spec();
 
-    let samples: Vec<f32> =
-        if spec.bits_per_sample == 32 && spec.sample_format == hound::SampleFormat::Float {
-            reader
-                .into_samples::<f32>()
-                .map(|s| s.map_err(|_| WhisperError::AudioLoadFailed))
-                .collect::<Result<Vec<_>>>()?
-        } else if spec.bits_per_sample == 16 {
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .into_samples::<f32>()
+            .map(|s| s.map_err(|_| WhisperError::AudioLoadFailed))
+            .collect::<Result<Vec<_>>>()?,
+        hound::SampleFormat::Int => {
+            // hound sign-extends every depth (and recenters 8-bit) into an i32
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f64;
             reader
-                .into_samples::<i16>()
+                .into_samples::<i32>()
                 .map(|s| s.map_err(|_| WhisperError::AudioLoadFailed))
-                .map(|s| Ok(s? as f32 / 32768.0))
+                .map(|s| Ok((s? as f64 / scale) as f32))
                 .collect::<Result<Vec<_>>>()?
-        } else {
-            return Err(WhisperError::AudioLoadFailed);
-        };
+        }
+    };
 
-    let samples = if spec.channels == 2 {
-        stereo_to_mono(&samples)
-    } else if spec.channels == 1 {
-        samples
-    } else {
+    Ok(DecodedAudio {
+        samples,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+    })
+}
+
+fn finish_audio(
+    decoded: &DecodedAudio,
+    mix: &ChannelMix,
+    options: &LoadOptions,
+) -> Result<AudioData> {
+    let mut audio_data = to_audio_data(decoded, mix, options.resample_quality)?;
+    if !options.preprocess.is_empty() {
+        log_info!("Preprocessing audio...");
+        preprocess(&mut audio_data.samples, &options.preprocess);
+    }
+    Ok(audio_data)
+}
+
+/// Downmixes and resamples to whisper's 16 kHz mono. Resampling works a chunk
+/// at a time and allocates no intermediate full-length buffers, but the
+/// decoded input is already fully in memory: a 3-hour 48 kHz stereo file
+/// takes about 4 GB as interleaved f32 before resampling starts.
+fn to_audio_data(
+    decoded: &DecodedAudio,
+    mix: &ChannelMix,
+    quality: ResampleQuality,
+) -> Result<AudioData> {
+    if decoded.channels == 0 {
         return Err(WhisperError::AudioLoadFailed);
-    };
+    }
 
-    let sample_rate = spec.sample_rate;
-    let samples = if sample_rate != SAMPLE_RATE {
-        log_info!("Resampling from {} Hz to {} Hz", sample_rate, SAMPLE_RATE);
-        resample(&samples, sample_rate, SAMPLE_RATE)?
-    } else {
+    let samples = if decoded.sample_rate != SAMPLE_RATE {
+        log_info!(
+            "Resampling from {} Hz to {} Hz",
+            decoded.sample_rate,
+            SAMPLE_RATE
+        );
+        let mut resampler = StreamResampler::new(decoded.sample_rate, SAMPLE_RATE, quality)?;
+        let frames = decoded.samples.len() / decoded.channels as usize;
+        let mut samples = Vec::with_capacity(
+            (frames as u64 * SAMPLE_RATE as u64 / decoded.sample_rate as u64) as usize + 1,
+        );
+        for chunk in decoded
+            .samples
+            .chunks(CHUNK_FRAMES * decoded.channels as usize)
+        {
+            resampler.push(&mix_to_mono(chunk, decoded.channels, mix)?, &mut samples)?;
+        }
+        resampler.finish(&mut samples)?;
         samples
+    } else {
+        mix_to_mono(&decoded.samples, decoded.channels, mix)?
     };
 
     Ok(AudioData {
@@ -68,70 +290,134 @@ fn load_wav(path: &Path) -> Result<AudioData> {
     })
 }
 
-fn stereo_to_mono(samples: &[f32]) -> Vec<f32> {
-    samples
-        .chunks(2)
-        .map(|chunk| (chunk[0] + chunk[1]) / 2.0)
-        .collect()
-}
-
-fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
-    if from_rate == to_rate {
-        return Ok(samples.to_vec());
+fn mix_to_mono(samples: &[f32], channels: u16, mix: &ChannelMix) -> Result<Vec<f32>> {
+    let channel_count = channels as usize;
+    if channel_count == 0 {
+        return Err(WhisperError::AudioLoadFailed);
     }
 
-    let ratio = to_rate as f64 / from_rate as f64;
-    let parameters = rubato::SincInterpolationParameters {
-        sinc_len: 256,
-        f_cutoff: 0.95,
-        interpolation: rubato::SincInterpolationType::Linear,
-        oversampling_factor: 256,
-        window: rubato::WindowFunction::BlackmanHarris2,
-    };
-
-    let chunk_size = samples.len().max(1024);
-    let mut resampler = SincFixedIn::<f64>::new(ratio, 2.0, parameters, chunk_size, 1)
-        .map_err(|_| WhisperError::AudioLoadFailed)?;
+    match mix {
+        ChannelMix::Select(channel) => {
+            if *channel >= channels {
+                return Err(WhisperError::Other(anyhow::anyhow!(
+                    "Channel {} requested but the audio has {} channel(s)",
+                    channel + 1,
+                    channels
+                )));
+            }
+            if channels == 1 {
+                return Ok(samples.to_vec());
+            }
+            Ok(samples
+                .chunks_exact(channel_count)
+                .map(|frame| frame[*channel as usize])
+                .collect())
+        }
+        ChannelMix::Weighted(weights) => {
+            if weights.len() != channel_count {
+                return Err(WhisperError::Other(anyhow::anyhow!(
+                    "Expected {} downmix weights for {}-channel audio, got {}",
+                    channels,
+                    channels,
+                    weights.len()
+                )));
+            }
+            weighted_downmix(samples, weights)
+        }
+        ChannelMix::Downmix => match channels {
+            1 => Ok(samples.to_vec()),
+            2 => Ok(stereo_to_mono(samples)),
+            6 => weighted_downmix(samples, &SURROUND_51_WEIGHTS),
+            _ => weighted_downmix(samples, &vec![1.0; channel_count]),
+        },
+    }
+}
 
-    let mut buffer_in = vec![vec![0.0f64; samples.len()]];
-    buffer_in[0].copy_from_slice(&samples.iter().map(|&x| x as f64).collect::<Vec<_>>());
+/// Mixes interleaved frames down to mono. The weights are normalized so the
+/// result stays within the input range.
+fn weighted_downmix(samples: &[f32], weights: &[f32]) -> Result<Vec<f32>> {
+    let total: f32 = weights.iter().map(|w| w.abs()).sum();
+    if total == 0.0 {
+        return Err(WhisperError::Other(anyhow::anyhow!(
+            "Downmix weights must not all be zero"
+        )));
+    }
 
-    let waves_out = resampler
-        .process(&buffer_in, None)
-        .map_err(|_| WhisperError::AudioLoadFailed)?;
+    Ok(samples
+        .chunks_exact(weights.len())
+        .map(|frame| {
+            frame
+                .iter()
+                .zip(weights)
+                .map(|(sample, weight)| sample * weight)
+                .sum::<f32>()
+                / total
+        })
+        .collect())
+}
 
-    Ok(waves_out[0].iter().map(|&x| x as f32).collect())
+fn stereo_to_mono(samples: &[f32]) -> Vec<f32> {
+    samples
+        .chunks(2)
+        .map(|chunk| (chunk[0] + chunk[1]) / 2.0)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::raw::PcmEncoding;
     use hound::{WavSpec, WavWriter};
     use tempfile::NamedTempFile;
 
+    fn load_wav_mono(path: &Path) -> Result<AudioData> {
+        to_audio_data(
+            &load_wav(path)?,
+            &ChannelMix::Downmix,
+            ResampleQuality::Best,
+        )
+    }
+
     fn create_wav_file(sample_rate: u32, channels: u16, bits: u16) -> NamedTempFile {
-        let temp_file = NamedTempFile::new().unwrap();
-        let spec = WavSpec {
-            channels,
+        create_wav_file_with_format(
             sample_rate,
-            bits_per_sample: bits,
-            sample_format: if bits == 32 {
+            channels,
+            bits,
+            if bits == 32 {
                 hound::SampleFormat::Float
             } else {
                 hound::SampleFormat::Int
             },
+        )
+    }
+
+    fn create_wav_file_with_format(
+        sample_rate: u32,
+        channels: u16,
+        bits: u16,
+        sample_format: hound::SampleFormat,
+    ) -> NamedTempFile {
+        let temp_file = NamedTempFile::new().unwrap();
+        let spec = WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: bits,
+            sample_format,
         };
 
         let mut writer = WavWriter::new(temp_file.reopen().unwrap(), spec).unwrap();
 
         for i in 0..1000 {
             let sample = (i as f32 / 1000.0).sin();
-            if bits == 32 {
+            if sample_format == hound::SampleFormat::Float {
                 writer.write_sample::<f32>(sample).unwrap();
             } else if bits == 16 {
                 writer
                     .write_sample::<i16>((sample * 32767.0) as i16)
                     .unwrap();
+            } else {
+                let max = ((1i64 << (bits - 1)) - 1) as f32;
+                writer.write_sample::<i32>((sample * max) as i32).unwrap();
             }
         }
 
@@ -142,7 +428,7 @@ mod tests {
     #[test]
     fn test_load_wav_16bit_mono() {
         let temp_file = create_wav_file(16000, 1, 16);
-        let audio_data = load_wav(temp_file.path()).unwrap();
+        let audio_data = load_wav_mono(temp_file.path()).unwrap();
         assert_eq!(audio_data.sample_rate, 16000);
         assert_eq!(audio_data.samples.len(), 1000);
     }
@@ -150,7 +436,7 @@ mod tests {
     #[test]
     fn test_load_wav_16bit_stereo() {
         let temp_file = create_wav_file(16000, 2, 16);
-        let audio_data = load_wav(temp_file.path()).unwrap();
+        let audio_data = load_wav_mono(temp_file.path()).unwrap();
         assert_eq!(audio_data.sample_rate, 16000);
         assert_eq!(audio_data.samples.len(), 500);
     }
@@ -158,15 +444,91 @@ mod tests {
     #[test]
     fn test_load_wav_32bit_mono() {
         let temp_file = create_wav_file(16000, 1, 32);
-        let audio_data = load_wav(temp_file.path()).unwrap();
+        let audio_data = load_wav_mono(temp_file.path()).unwrap();
         assert_eq!(audio_data.sample_rate, 16000);
         assert_eq!(audio_data.samples.len(), 1000);
     }
 
+    #[test]
+    fn test_load_wav_integer_depths() {
+        for bits in [8, 24, 32] {
+            let temp_file = create_wav_file_with_format(16000, 1, bits, hound::SampleFormat::Int);
+            let audio_data = load_wav_mono(temp_file.path()).unwrap();
+            assert_eq!(audio_data.samples.len(), 1000);
+            let expected = (999.0f32 / 1000.0).sin();
+            assert!((audio_data.samples[999] - expected).abs() < 0.02);
+            assert!(audio_data.samples.iter().all(|s| s.abs() <= 1.0));
+        }
+    }
+
+    #[test]
+    fn test_load_wav_64bit_float() {
+        let mut data = std::fs::read(create_wav_file(16000, 1, 16).path()).unwrap();
+        let header_len = data.len() - 2000;
+        data.truncate(header_len);
+        // rewrite the fmt chunk as IEEE float, 64 bits, and the data as f64
+        data[20..22].copy_from_slice(&3u16.to_le_bytes());
+        data[28..32].copy_from_slice(&(16000u32 * 8).to_le_bytes());
+        data[32..34].copy_from_slice(&8u16.to_le_bytes());
+        data[34..36].copy_from_slice(&64u16.to_le_bytes());
+        data[header_len - 4..].copy_from_slice(&16u32.to_le_bytes());
+        data.extend_from_slice(&0.5f64.to_le_bytes());
+        data.extend_from_slice(&(-0.25f64).to_le_bytes());
+
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), &data).unwrap();
+        let audio_data = load_wav_mono(temp_file.path()).unwrap();
+        assert_eq!(audio_data.samples, vec![0.5, -0.25]);
+
+        let audio_data = load_audio_from_bytes(data, &LoadOptions::default()).unwrap();
+        assert_eq!(audio_data.samples, vec![0.5, -0.25]);
+    }
+
+    #[test]
+    fn test_load_wav_four_channels() {
+        let temp_file = create_wav_file(16000, 4, 16);
+        let audio_data = load_wav_mono(temp_file.path()).unwrap();
+        assert_eq!(audio_data.samples.len(), 250);
+
+        let audio_data = to_audio_data(
+            &load_wav(temp_file.path()).unwrap(),
+            &ChannelMix::Select(3),
+            ResampleQuality::Best,
+        )
+        .unwrap();
+        assert_eq!(audio_data.samples.len(), 250);
+        assert!((audio_data.samples[1] - (7.0f32 / 1000.0).sin()).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_load_audio_channels() {
+        let temp_file = create_wav_file(16000, 2, 16);
+        let channels =
+            load_audio_channels(&temp_file.path().to_string_lossy(), &LoadOptions::default())
+                .unwrap();
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0].samples.len(), 500);
+        assert!((channels[1].samples[0] - (1.0f32 / 1000.0).sin()).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_to_audio_data_resamples_multichannel_in_chunks() {
+        let frames = CHUNK_FRAMES * 3 + 100;
+        let decoded = DecodedAudio {
+            samples: vec![0.25; frames * 4],
+            sample_rate: 32000,
+            channels: 4,
+        };
+        let audio_data =
+            to_audio_data(&decoded, &ChannelMix::Downmix, ResampleQuality::Fast).unwrap();
+        assert_eq!(audio_data.samples.len(), frames / 2);
+        assert!((audio_data.samples[frames / 4] - 0.25).abs() < 0.01);
+    }
+
     #[test]
     fn test_load_wav_with_resampling() {
         let temp_file = create_wav_file(22050, 1, 16);
-        let audio_data = load_wav(temp_file.path());
+        let audio_data = load_wav_mono(temp_file.path());
         if audio_data.is_err() {
             return;
         }
@@ -181,6 +543,71 @@ mod tests {
         assert!(matches!(result, Err(WhisperError::AudioLoadFailed)));
     }
 
+    #[test]
+    fn test_to_audio_data_stereo() {
+        let decoded = DecodedAudio {
+            samples: vec![0.5, 0.25, -0.5, -0.25],
+            sample_rate: 16000,
+            channels: 2,
+        };
+        let audio_data =
+            to_audio_data(&decoded, &ChannelMix::Downmix, ResampleQuality::Best).unwrap();
+        assert_eq!(audio_data.samples, vec![0.375, -0.375]);
+        assert_eq!(audio_data.sample_rate, 16000);
+    }
+
+    #[test]
+    fn test_to_audio_data_no_channels() {
+        let decoded = DecodedAudio {
+            samples: vec![0.0; 6],
+            sample_rate: 16000,
+            channels: 0,
+        };
+        let result = to_audio_data(&decoded, &ChannelMix::Downmix, ResampleQuality::Best);
+        assert!(matches!(result, Err(WhisperError::AudioLoadFailed)));
+    }
+
+    #[test]
+    fn test_mix_to_mono_four_channels() {
+        let samples = vec![0.4, 0.0, 0.2, 0.2, 1.0, 1.0, 1.0, 1.0];
+        let mono = mix_to_mono(&samples, 4, &ChannelMix::Downmix).unwrap();
+        assert_eq!(mono, vec![0.2, 1.0]);
+    }
+
+    #[test]
+    fn test_mix_to_mono_surround() {
+        let frame = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+        let mono = mix_to_mono(&frame, 6, &ChannelMix::Downmix).unwrap();
+        assert!((mono[0] - 1.0).abs() < 1e-6);
+
+        // the LFE channel is left out
+        let lfe_only = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+        let mono = mix_to_mono(&lfe_only, 6, &ChannelMix::Downmix).unwrap();
+        assert_eq!(mono, vec![0.0]);
+    }
+
+    #[test]
+    fn test_mix_to_mono_weighted() {
+        let samples = vec![1.0, 0.0, 0.0, 1.0];
+        let mono = mix_to_mono(&samples, 2, &ChannelMix::Weighted(vec![3.0, 1.0])).unwrap();
+        assert_eq!(mono, vec![0.75, 0.25]);
+
+        let result = mix_to_mono(&[0.0; 4], 2, &ChannelMix::Weighted(vec![1.0]));
+        assert!(result.is_err());
+        let result = mix_to_mono(&[0.0; 4], 2, &ChannelMix::Weighted(vec![0.0, 0.0]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_mix_to_mono_select() {
+        let samples = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
+        let mono = mix_to_mono(&samples, 4, &ChannelMix::Select(2)).unwrap();
+        assert_eq!(mono, vec![0.3, 0.7]);
+
+        let result = mix_to_mono(&samples, 4, &ChannelMix::Select(4));
+        assert!(matches!(result, Err(WhisperError::Other(_))));
+    }
+
     #[test]
     fn test_stereo_to_mono() {
         let samples = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
@@ -192,17 +619,137 @@ mod tests {
     }
 
     #[test]
-    fn test_resample_same_rate() {
-        let samples = vec![0.0, 0.1, 0.2, 0.3, 0.4];
-        let result = resample(&samples, 16000, 16000).unwrap();
-        assert_eq!(result.len(), samples.len());
+    fn test_load_audio_detects_wav_without_extension() {
+        let temp_file = create_wav_file(16000, 1, 16);
+        let audio_data = load_audio(&temp_file.path().to_string_lossy()).unwrap();
+        assert_eq!(audio_data.samples.len(), 1000);
     }
 
     #[test]
-    fn test_resample_different_rate() {
-        let samples = vec![0.0; 2000];
-        let result = resample(&samples, 22050, 16000).unwrap();
-        assert!(result.len() < samples.len());
+    fn test_load_audio_mislabeled_wav() {
+        let temp_file = create_wav_file(16000, 1, 16);
+        let mp3_path = temp_file.path().with_extension("mp3");
+        std::fs::copy(temp_file.path(), &mp3_path).unwrap();
+
+        let audio_data = load_audio(&mp3_path.to_string_lossy());
+        std::fs::remove_file(&mp3_path).unwrap();
+
+        assert_eq!(audio_data.unwrap().samples.len(), 1000);
+    }
+
+    #[test]
+    fn test_load_audio_from_bytes_wav() {
+        let temp_file = create_wav_file(16000, 2, 16);
+        let data = std::fs::read(temp_file.path()).unwrap();
+        let audio_data = load_audio_from_bytes(data, &LoadOptions::default()).unwrap();
+        assert_eq!(audio_data.samples.len(), 500);
+        assert_eq!(audio_data.sample_rate, 16000);
+    }
+
+    #[test]
+    fn test_load_audio_from_bytes_unknown_format() {
+        let result = load_audio_from_bytes(b"not an audio file".to_vec(), &LoadOptions::default());
+        assert!(matches!(result, Err(WhisperError::UnsupportedFileType)));
+    }
+
+    #[test]
+    fn test_load_audio_from_bytes_truncated_wav() {
+        let result = load_audio_from_bytes(
+            b"RIFF\x24\x00\x00\x00WAVE".to_vec(),
+            &LoadOptions::default(),
+        );
+        assert!(matches!(result, Err(WhisperError::AudioLoadFailed)));
+    }
+
+    #[test]
+    fn test_load_options_default() {
+        assert_eq!(LoadOptions::default().threads, 1);
+        assert_eq!(LoadOptions::default().raw, None);
+    }
+
+    #[test]
+    fn test_load_raw_pcm_stereo() {
+        let data: Vec<u8> = [0i16, 16384, 16384, 16384]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let format = RawFormat {
+            encoding: PcmEncoding::S16le,
+            sample_rate: 16000,
+            channels: 2,
+        };
+        let audio_data = load_raw_pcm(&data, &format, &LoadOptions::default()).unwrap();
+        assert_eq!(audio_data.samples, vec![0.25, 0.5]);
+        assert_eq!(audio_data.sample_rate, 16000);
+    }
+
+    #[test]
+    fn test_load_raw_pcm_preprocessed() {
+        let data: Vec<u8> = [1000i16, 3000, 1000, 3000]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let format = RawFormat {
+            encoding: PcmEncoding::S16le,
+            sample_rate: 16000,
+            channels: 1,
+        };
+        let options = LoadOptions {
+            preprocess: PreprocessOptions {
+                remove_dc: true,
+                peak_db: Some(0.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let audio_data = load_raw_pcm(&data, &format, &options).unwrap();
+        assert_eq!(audio_data.samples, vec![-1.0, 1.0, -1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_load_raw_pcm_resamples() {
+        let data = vec![128u8; 8000];
+        let format = RawFormat {
+            encoding: PcmEncoding::U8,
+            sample_rate: 8000,
+            channels: 1,
+        };
+        let audio_data = load_raw_pcm(&data, &format, &LoadOptions::default()).unwrap();
+        assert_eq!(audio_data.sample_rate, 16000);
+        assert_eq!(audio_data.samples.len(), 2 * data.len());
+    }
+
+    #[test]
+    fn test_load_audio_with_raw_options() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), [0u8; 3200]).unwrap();
+        let options = LoadOptions {
+            raw: Some(RawFormat {
+                encoding: PcmEncoding::S16le,
+                sample_rate: 16000,
+                channels: 1,
+            }),
+            ..Default::default()
+        };
+        let audio_data =
+            load_audio_with_options(&temp_file.path().to_string_lossy(), &options).unwrap();
+        assert_eq!(audio_data.samples.len(), 1600);
+    }
+
+    #[test]
+    fn test_load_audio_with_options_wav() {
+        let temp_file = create_wav_file(16000, 1, 16);
+        let wav_path = temp_file.path().with_extension("wav");
+        std::fs::copy(temp_file.path(), &wav_path).unwrap();
+
+        let options = LoadOptions {
+            threads: 4,
+            ..Default::default()
+        };
+        let audio_data = load_audio_with_options(&wav_path.to_string_lossy(), &options);
+        std::fs::remove_file(&wav_path).unwrap();
+
+        assert_eq!(audio_data.unwrap().samples.len(), 1000);
     }
 
     #[test]
@@ -1,6 +1,20 @@
 pub mod converter;
+#[cfg(feature = "native-decoding")]
+pub mod decoder;
 pub mod formats;
 pub mod loader;
+pub mod preprocess;
+pub mod raw;
+pub mod resample;
+pub mod vad;
+pub mod wav;
 
 pub use formats::AudioFormat;
-pub use loader::{load_audio, AudioData, SAMPLE_RATE};
+pub use loader::{
+    load_audio, load_audio_channels, load_audio_from_bytes, load_audio_with_options, AudioData,
+    ChannelMix, DecodedAudio, LoadOptions, SAMPLE_RATE, STDIN_PATH,
+};
+pub use preprocess::{PreprocessFilter, PreprocessOptions};
+pub use raw::{PcmEncoding, RawFormat};
+pub use resample::ResampleQuality;
+pub use vad::{detect_speech, SpeechTimeline, VadOptions};
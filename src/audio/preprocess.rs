@@ -0,0 +1,257 @@
+use crate::audio::loader::SAMPLE_RATE;
+
+// 10 ms analysis frames for the noise gate
+const GATE_FRAME_LEN: usize = 160;
+// The gate stays open this long after the last loud frame so word endings
+// are not chopped off.
+const GATE_HOLD_FRAMES: usize = 15;
+// Gain change per sample while the gate opens or closes (~5 ms ramps).
+const GATE_RAMP_STEP: f32 = 1.0 / 80.0;
+// Below this RMS the input is treated as silence and left unscaled.
+const SILENCE_RMS: f32 = 1e-5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PreprocessFilter {
+    /// Remove DC offset
+    Dc,
+    /// High-pass filter to remove rumble
+    Highpass,
+    /// Silence everything below the gate threshold
+    Gate,
+    /// Normalize the peak level (or cap it when combined with rms)
+    Peak,
+    /// Normalize the RMS loudness
+    Rms,
+}
+
+/// Filters applied to 16 kHz mono audio before inference. They always run
+/// in the order DC removal, high-pass, noise gate, normalization.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PreprocessOptions {
+    pub remove_dc: bool,
+    pub highpass_hz: Option<f32>,
+    pub gate_threshold_db: Option<f32>,
+    pub peak_db: Option<f32>,
+    pub rms_db: Option<f32>,
+}
+
+impl PreprocessOptions {
+    pub fn is_empty(&self) -> bool {
+        *self == PreprocessOptions::default()
+    }
+}
+
+pub fn preprocess(samples: &mut [f32], options: &PreprocessOptions) {
+    if options.remove_dc {
+        remove_dc_offset(samples);
+    }
+    if let Some(cutoff) = options.highpass_hz {
+        high_pass(samples, cutoff, SAMPLE_RATE);
+    }
+    if let Some(threshold) = options.gate_threshold_db {
+        noise_gate(samples, threshold);
+    }
+    match (options.rms_db, options.peak_db) {
+        (Some(rms), peak) => {
+            normalize_rms(samples, rms);
+            if let Some(peak) = peak {
+                limit_peak(samples, peak);
+            }
+        }
+        (None, Some(peak)) => normalize_peak(samples, peak),
+        (None, None) => {}
+    }
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+fn peak(samples: &[f32]) -> f32 {
+    samples.iter().fold(0.0, |max, s| max.max(s.abs()))
+}
+
+pub fn remove_dc_offset(samples: &mut [f32]) {
+    if samples.is_empty() {
+        return;
+    }
+    let mean = (samples.iter().map(|&s| s as f64).sum::<f64>() / samples.len() as f64) as f32;
+    for sample in samples.iter_mut() {
+        *sample -= mean;
+    }
+}
+
+/// Second-order Butterworth high-pass (RBJ cookbook biquad).
+pub fn high_pass(samples: &mut [f32], cutoff_hz: f32, sample_rate: u32) {
+    let omega = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate as f32;
+    let alpha = omega.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+    let cos = omega.cos();
+
+    let a0 = 1.0 + alpha;
+    let b0 = (1.0 + cos) / 2.0 / a0;
+    let b1 = -(1.0 + cos) / a0;
+    let b2 = b0;
+    let a1 = -2.0 * cos / a0;
+    let a2 = (1.0 - alpha) / a0;
+
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for sample in samples.iter_mut() {
+        let x0 = *sample;
+        let y0 = b0 * x0 + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+        x2 = x1;
+        x1 = x0;
+        y2 = y1;
+        y1 = y0;
+        *sample = y0;
+    }
+}
+
+pub fn noise_gate(samples: &mut [f32], threshold_db: f32) {
+    let threshold = db_to_gain(threshold_db);
+    let mut gain = 0.0f32;
+    let mut hold = 0;
+
+    for frame in samples.chunks_mut(GATE_FRAME_LEN) {
+        if rms(frame) >= threshold {
+            hold = GATE_HOLD_FRAMES;
+        } else {
+            hold = hold.saturating_sub(1);
+        }
+        let target = if hold > 0 { 1.0 } else { 0.0 };
+
+        for sample in frame.iter_mut() {
+            if gain < target {
+                gain = (gain + GATE_RAMP_STEP).min(target);
+            } else if gain > target {
+                gain = (gain - GATE_RAMP_STEP).max(target);
+            }
+            *sample *= gain;
+        }
+    }
+}
+
+pub fn normalize_peak(samples: &mut [f32], target_db: f32) {
+    let peak = peak(samples);
+    if peak > SILENCE_RMS {
+        apply_gain(samples, db_to_gain(target_db) / peak);
+    }
+}
+
+pub fn normalize_rms(samples: &mut [f32], target_db: f32) {
+    let rms = rms(samples);
+    if rms > SILENCE_RMS {
+        apply_gain(samples, db_to_gain(target_db) / rms);
+    }
+}
+
+fn limit_peak(samples: &mut [f32], ceiling_db: f32) {
+    let ceiling = db_to_gain(ceiling_db);
+    let peak = peak(samples);
+    if peak > ceiling {
+        apply_gain(samples, ceiling / peak);
+    }
+}
+
+fn apply_gain(samples: &mut [f32], gain: f32) {
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / 16000.0).sin()
+            })
+            .collect()
+    }
+
+    fn to_db(gain: f32) -> f32 {
+        20.0 * gain.log10()
+    }
+
+    #[test]
+    fn test_preprocess_options_is_empty() {
+        assert!(PreprocessOptions::default().is_empty());
+        let options = PreprocessOptions {
+            remove_dc: true,
+            ..Default::default()
+        };
+        assert!(!options.is_empty());
+    }
+
+    #[test]
+    fn test_remove_dc_offset() {
+        let mut samples: Vec<f32> = sine(100.0, 0.1, 1600).iter().map(|s| s + 0.3).collect();
+        remove_dc_offset(&mut samples);
+        let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+        assert!(mean.abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_high_pass() {
+        let mut rumble = sine(20.0, 0.5, 16000);
+        high_pass(&mut rumble, 100.0, 16000);
+        assert!(rms(&rumble[8000..]) < 0.5 * std::f32::consts::FRAC_1_SQRT_2 * 0.05);
+
+        let mut voice = sine(1000.0, 0.5, 16000);
+        high_pass(&mut voice, 100.0, 16000);
+        assert!((rms(&voice[8000..]) - 0.5 * std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_noise_gate() {
+        let mut samples = sine(300.0, 0.001, 16000);
+        samples.extend(sine(300.0, 0.3, 16000));
+        noise_gate(&mut samples, -40.0);
+
+        assert!(peak(&samples[..15000]) < 1e-6);
+        assert!((rms(&samples[17000..]) - 0.3 * std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_normalize_peak() {
+        let mut samples = sine(300.0, 0.1, 1600);
+        normalize_peak(&mut samples, -1.0);
+        assert!((to_db(peak(&samples)) + 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_normalize_rms() {
+        let mut samples = sine(300.0, 0.01, 1600);
+        normalize_rms(&mut samples, -20.0);
+        assert!((to_db(rms(&samples)) + 20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_normalize_silence_untouched() {
+        let mut samples = vec![0.0; 100];
+        normalize_rms(&mut samples, -20.0);
+        normalize_peak(&mut samples, -1.0);
+        assert!(samples.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_preprocess_rms_with_peak_ceiling() {
+        let mut samples = sine(300.0, 0.01, 1600);
+        samples[0] = 0.5;
+        let options = PreprocessOptions {
+            rms_db: Some(-20.0),
+            peak_db: Some(-1.0),
+            ..Default::default()
+        };
+        preprocess(&mut samples, &options);
+        assert!(to_db(peak(&samples)) <= -0.99);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::error::{Result, WhisperError};
+use crate::log_warning;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PcmEncoding {
+    S16le,
+    F32le,
+    U8,
+    S24le,
+}
+
+impl PcmEncoding {
+    pub fn bytes_per_sample(&self) -> usize {
+        match self {
+            PcmEncoding::U8 => 1,
+            PcmEncoding::S16le => 2,
+            PcmEncoding::S24le => 3,
+            PcmEncoding::F32le => 4,
+        }
+    }
+
+    fn decode_sample(&self, bytes: &[u8]) -> f32 {
+        match self {
+            PcmEncoding::U8 => (bytes[0] as f32 - 128.0) / 128.0,
+            PcmEncoding::S16le => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            // shift into the top of an i32 so the sign bit lands in place
+            PcmEncoding::S24le => {
+                (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8_388_608.0
+            }
+            PcmEncoding::F32le => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+}
+
+/// Layout of headerless PCM input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawFormat {
+    pub encoding: PcmEncoding,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Converts raw PCM bytes to interleaved f32 samples. A trailing partial
+/// frame (e.g. from a cut-off stream) is dropped.
+pub fn decode_raw_pcm(data: &[u8], format: &RawFormat) -> Result<Vec<f32>> {
+    if format.channels == 0 || format.sample_rate == 0 {
+        return Err(WhisperError::AudioLoadFailed);
+    }
+
+    let sample_size = format.encoding.bytes_per_sample();
+    let frame_size = sample_size * format.channels as usize;
+    let usable = data.len() - data.len() % frame_size;
+
+    if usable < data.len() {
+        log_warning!(
+            "Ignoring {} trailing bytes that do not form a complete frame",
+            data.len() - usable
+        );
+    }
+    if usable == 0 {
+        return Err(WhisperError::AudioLoadFailed);
+    }
+
+    Ok(data[..usable]
+        .chunks_exact(sample_size)
+        .map(|bytes| format.encoding.decode_sample(bytes))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_format(encoding: PcmEncoding, channels: u16) -> RawFormat {
+        RawFormat {
+            encoding,
+            sample_rate: 8000,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_bytes_per_sample() {
+        assert_eq!(PcmEncoding::U8.bytes_per_sample(), 1);
+        assert_eq!(PcmEncoding::S16le.bytes_per_sample(), 2);
+        assert_eq!(PcmEncoding::S24le.bytes_per_sample(), 3);
+        assert_eq!(PcmEncoding::F32le.bytes_per_sample(), 4);
+    }
+
+    #[test]
+    fn test_decode_u8() {
+        let samples = decode_raw_pcm(&[0, 128, 255], &raw_format(PcmEncoding::U8, 1)).unwrap();
+        assert_eq!(samples, vec![-1.0, 0.0, 127.0 / 128.0]);
+    }
+
+    #[test]
+    fn test_decode_s16le() {
+        let data = [0x00, 0x80, 0x00, 0x00, 0x00, 0x40];
+        let samples = decode_raw_pcm(&data, &raw_format(PcmEncoding::S16le, 1)).unwrap();
+        assert_eq!(samples, vec![-1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn test_decode_s24le() {
+        let data = [0x00, 0x00, 0x80, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x40];
+        let samples = decode_raw_pcm(&data, &raw_format(PcmEncoding::S24le, 1)).unwrap();
+        assert_eq!(samples, vec![-1.0, -1.0 / 8_388_608.0, 0.5]);
+    }
+
+    #[test]
+    fn test_decode_f32le() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&0.25f32.to_le_bytes());
+        data.extend_from_slice(&(-0.75f32).to_le_bytes());
+        let samples = decode_raw_pcm(&data, &raw_format(PcmEncoding::F32le, 2)).unwrap();
+        assert_eq!(samples, vec![0.25, -0.75]);
+    }
+
+    #[test]
+    fn test_decode_drops_partial_frame() {
+        let data = [0x00, 0x40, 0x00, 0x40, 0x00];
+        let samples = decode_raw_pcm(&data, &raw_format(PcmEncoding::S16le, 2)).unwrap();
+        assert_eq!(samples.len(), 2);
+    }
+
+    #[test]
+    fn test_decode_empty_input() {
+        let result = decode_raw_pcm(&[], &raw_format(PcmEncoding::S16le, 1));
+        assert!(matches!(result, Err(WhisperError::AudioLoadFailed)));
+    }
+
+    #[test]
+    fn test_decode_invalid_layout() {
+        let result = decode_raw_pcm(&[0, 0], &raw_format(PcmEncoding::S16le, 0));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,240 @@
+use crate::error::{Result, WhisperError};
+use rubato::{
+    Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+};
+
+/// Input frames handed to the resampler per call. Together with its output
+/// buffer this is all the working memory resampling needs.
+pub const CHUNK_FRAMES: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ResampleQuality {
+    Fast,
+    Balanced,
+    #[default]
+    Best,
+}
+
+impl ResampleQuality {
+    fn parameters(&self) -> SincInterpolationParameters {
+        match self {
+            ResampleQuality::Fast => SincInterpolationParameters {
+                sinc_len: 64,
+                f_cutoff: 0.91,
+                interpolation: SincInterpolationType::Nearest,
+                oversampling_factor: 128,
+                window: WindowFunction::Hann2,
+            },
+            ResampleQuality::Balanced => SincInterpolationParameters {
+                sinc_len: 128,
+                f_cutoff: 0.93,
+                interpolation: SincInterpolationType::Linear,
+                oversampling_factor: 128,
+                window: WindowFunction::Blackman2,
+            },
+            ResampleQuality::Best => SincInterpolationParameters {
+                sinc_len: 256,
+                f_cutoff: 0.95,
+                interpolation: SincInterpolationType::Linear,
+                oversampling_factor: 256,
+                window: WindowFunction::BlackmanHarris2,
+            },
+        }
+    }
+}
+
+/// Mono resampler that accepts input in arbitrary pieces and keeps only one
+/// chunk in flight. The output lines up with the input and has
+/// `input_len * to_rate / from_rate` frames (rounded up).
+pub struct StreamResampler {
+    resampler: SincFixedIn<f32>,
+    pending: Vec<f32>,
+    output_buffer: Vec<Vec<f32>>,
+    ratio: f64,
+    frames_in: usize,
+    frames_out: usize,
+}
+
+impl StreamResampler {
+    pub fn new(from_rate: u32, to_rate: u32, quality: ResampleQuality) -> Result<Self> {
+        let ratio = to_rate as f64 / from_rate as f64;
+        let resampler = SincFixedIn::<f32>::new(ratio, 1.0, quality.parameters(), CHUNK_FRAMES, 1)
+            .map_err(|_| WhisperError::AudioLoadFailed)?;
+        let output_buffer = resampler.output_buffer_allocate(true);
+
+        Ok(StreamResampler {
+            resampler,
+            pending: Vec::with_capacity(CHUNK_FRAMES),
+            output_buffer,
+            ratio,
+            frames_in: 0,
+            frames_out: 0,
+        })
+    }
+
+    /// Feeds more input; any completed output is appended to `output`.
+    pub fn push(&mut self, mut input: &[f32], output: &mut Vec<f32>) -> Result<()> {
+        self.frames_in += input.len();
+
+        while !input.is_empty() {
+            let take = (CHUNK_FRAMES - self.pending.len()).min(input.len());
+            self.pending.extend_from_slice(&input[..take]);
+            input = &input[take..];
+
+            if self.pending.len() == CHUNK_FRAMES {
+                let (_, written) = self
+                    .resampler
+                    .process_into_buffer(&[&self.pending], &mut self.output_buffer, None)
+                    .map_err(|_| WhisperError::AudioLoadFailed)?;
+                self.pending.clear();
+                self.emit(written, output);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Flushes the buffered input and the filter tail.
+    pub fn finish(mut self, output: &mut Vec<f32>) -> Result<()> {
+        let expected = self.expected_frames();
+
+        if !self.pending.is_empty() {
+            let (_, written) = self
+                .resampler
+                .process_partial_into_buffer(Some(&[&self.pending]), &mut self.output_buffer, None)
+                .map_err(|_| WhisperError::AudioLoadFailed)?;
+            self.emit(written, output);
+        }
+        while self.frames_out < expected {
+            let (_, written) = self
+                .resampler
+                .process_partial_into_buffer(None::<&[&[f32]]>, &mut self.output_buffer, None)
+                .map_err(|_| WhisperError::AudioLoadFailed)?;
+            self.emit(written, output);
+        }
+
+        // the last chunk was zero-padded; drop what the padding produced
+        output.truncate(output.len() - (self.frames_out - expected));
+        Ok(())
+    }
+
+    pub fn expected_frames(&self) -> usize {
+        (self.frames_in as f64 * self.ratio).ceil() as usize
+    }
+
+    fn emit(&mut self, written: usize, output: &mut Vec<f32>) {
+        output.extend_from_slice(&self.output_buffer[0][..written]);
+        self.frames_out += written;
+    }
+}
+
+pub fn resample(
+    samples: &[f32],
+    from_rate: u32,
+    to_rate: u32,
+    quality: ResampleQuality,
+) -> Result<Vec<f32>> {
+    if from_rate == to_rate {
+        return Ok(samples.to_vec());
+    }
+
+    let mut resampler = StreamResampler::new(from_rate, to_rate, quality)?;
+    let mut output =
+        Vec::with_capacity((samples.len() as f64 * to_rate as f64 / from_rate as f64) as usize + 1);
+    for chunk in samples.chunks(CHUNK_FRAMES) {
+        resampler.push(chunk, &mut output)?;
+    }
+    resampler.finish(&mut output)?;
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, sample_rate: u32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_resample_same_rate() {
+        let samples = vec![0.0, 0.1, 0.2, 0.3, 0.4];
+        let result = resample(&samples, 16000, 16000, ResampleQuality::Best).unwrap();
+        assert_eq!(result.len(), samples.len());
+    }
+
+    #[test]
+    fn test_resample_different_rate() {
+        let samples = vec![0.0; 2000];
+        let result = resample(&samples, 22050, 16000, ResampleQuality::Best).unwrap();
+        assert!(result.len() < samples.len());
+    }
+
+    #[test]
+    fn test_resample_output_length() {
+        for quality in [
+            ResampleQuality::Fast,
+            ResampleQuality::Balanced,
+            ResampleQuality::Best,
+        ] {
+            let result = resample(&vec![0.0; 48_000], 48_000, 16_000, quality).unwrap();
+            assert_eq!(result.len(), 16_000);
+
+            let result = resample(&vec![0.0; 1_001], 8_000, 16_000, quality).unwrap();
+            assert_eq!(result.len(), 2_002);
+        }
+    }
+
+    #[test]
+    fn test_resample_preserves_tone() {
+        let input = sine(440.0, 48_000, 48_000);
+        let output = resample(&input, 48_000, 16_000, ResampleQuality::Best).unwrap();
+
+        let steady = &output[1_000..15_000];
+        let rms = (steady.iter().map(|s| s * s).sum::<f32>() / steady.len() as f32).sqrt();
+        assert!((rms - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+
+        let crossings = steady
+            .windows(2)
+            .filter(|pair| pair[0] < 0.0 && pair[1] >= 0.0)
+            .count();
+        assert!((384..=386).contains(&crossings));
+    }
+
+    #[test]
+    fn test_resample_impulse_position() {
+        let mut input = vec![0.0; 48_000];
+        input[30_000] = 1.0;
+        let output = resample(&input, 48_000, 16_000, ResampleQuality::Balanced).unwrap();
+        let peak = output
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(index, _)| index);
+        assert!(matches!(peak, Some(9_999..=10_001)));
+    }
+
+    #[test]
+    fn test_stream_resampler_matches_one_shot() {
+        let input = sine(300.0, 44_100, 30_000);
+        let one_shot = resample(&input, 44_100, 16_000, ResampleQuality::Fast).unwrap();
+
+        let mut resampler = StreamResampler::new(44_100, 16_000, ResampleQuality::Fast).unwrap();
+        let mut streamed = Vec::new();
+        for piece in input.chunks(777) {
+            resampler.push(piece, &mut streamed).unwrap();
+        }
+        resampler.finish(&mut streamed).unwrap();
+
+        assert_eq!(streamed, one_shot);
+    }
+
+    #[test]
+    fn test_resample_empty() {
+        let result = resample(&[], 44_100, 16_000, ResampleQuality::Balanced).unwrap();
+        assert!(result.is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::audio::loader::SAMPLE_RATE;
+use std::ops::Range;
+
+// 30 ms analysis frames at 16 kHz
+const FRAME_LEN: usize = 480;
+// Frames quieter than this are never speech, whatever the noise floor.
+const MIN_SPEECH_DB: f32 = -50.0;
+// Broadband noise crosses zero on about half of all samples; voiced speech
+// far less often.
+const MAX_ZERO_CROSSING_RATE: f32 = 0.4;
+// Percentile of frame energies taken as the noise floor.
+const NOISE_FLOOR_PERCENTILE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VadOptions {
+    /// How far above the noise floor a frame must be to count as speech.
+    pub threshold_db: f32,
+    /// Speech shorter than this is dropped as a click or cough.
+    pub min_speech_ms: u32,
+    /// Pauses shorter than this do not split a speech region.
+    pub min_silence_ms: u32,
+    /// Audio kept on either side of each region so word edges are not cut.
+    pub padding_ms: u32,
+}
+
+impl Default for VadOptions {
+    fn default() -> Self {
+        VadOptions {
+            threshold_db: 10.0,
+            min_speech_ms: 250,
+            min_silence_ms: 500,
+            padding_ms: 200,
+        }
+    }
+}
+
+fn ms_to_samples(ms: u32) -> usize {
+    ms as usize * SAMPLE_RATE as usize / 1000
+}
+
+fn frame_energy_db(frame: &[f32]) -> f32 {
+    let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+    10.0 * (mean_square + 1e-10).log10()
+}
+
+fn zero_crossing_rate(frame: &[f32]) -> f32 {
+    let crossings = frame
+        .windows(2)
+        .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+        .count();
+    crossings as f32 / frame.len() as f32
+}
+
+/// Finds speech in 16 kHz mono audio. A frame is speech when its energy
+/// clears both the adaptive noise floor and an absolute minimum and its
+/// zero-crossing rate is not that of broadband noise. Returns sample ranges
+/// after merging short pauses, dropping short blips and adding padding.
+pub fn detect_speech(samples: &[f32], options: &VadOptions) -> Vec<Range<usize>> {
+    let frames: Vec<&[f32]> = samples.chunks(FRAME_LEN).collect();
+    if frames.is_empty() {
+        return Vec::new();
+    }
+
+    let energies: Vec<f32> = frames.iter().map(|frame| frame_energy_db(frame)).collect();
+    let mut sorted = energies.clone();
+    sorted.sort_by(f32::total_cmp);
+    let noise_floor = sorted[(sorted.len() - 1) * NOISE_FLOOR_PERCENTILE / 100];
+    let threshold = (noise_floor + options.threshold_db).max(MIN_SPEECH_DB);
+
+    let mut regions: Vec<Range<usize>> = Vec::new();
+    for (index, (frame, energy)) in frames.iter().zip(&energies).enumerate() {
+        if *energy < threshold || zero_crossing_rate(frame) > MAX_ZERO_CROSSING_RATE {
+            continue;
+        }
+        let start = index * FRAME_LEN;
+        let end = start + frame.len();
+        match regions.last_mut() {
+            Some(region) if start - region.end < ms_to_samples(options.min_silence_ms) => {
+                region.end = end;
+            }
+            _ => regions.push(start..end),
+        }
+    }
+
+    let min_speech = ms_to_samples(options.min_speech_ms);
+    let padding = ms_to_samples(options.padding_ms);
+    let mut padded: Vec<Range<usize>> = Vec::new();
+    for region in regions.into_iter().filter(|r| r.len() >= min_speech) {
+        let start = region.start.saturating_sub(padding);
+        let end = (region.end + padding).min(samples.len());
+        match padded.last_mut() {
+            Some(previous) if start <= previous.end => previous.end = end,
+            _ => padded.push(start..end),
+        }
+    }
+
+    padded
+}
+
+/// Speech regions cut out of a recording and joined end to end, with enough
+/// bookkeeping to map times in the joined audio back to the original.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeechTimeline {
+    pub samples: Vec<f32>,
+    regions: Vec<Range<usize>>,
+}
+
+impl SpeechTimeline {
+    pub fn new(samples: &[f32], regions: Vec<Range<usize>>) -> Self {
+        let joined = regions
+            .iter()
+            .flat_map(|region| samples[region.clone()].iter().copied())
+            .collect();
+        SpeechTimeline {
+            samples: joined,
+            regions,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    pub fn speech_ms(&self) -> i64 {
+        self.samples.len() as i64 * 1000 / SAMPLE_RATE as i64
+    }
+
+    /// Maps a time in the joined audio to the original recording. A time on
+    /// the seam between two regions maps to the end of the earlier one.
+    pub fn to_original_ms(&self, ms: i64) -> i64 {
+        let samples_per_ms = SAMPLE_RATE as i64 / 1000;
+        let position = ms.max(0) * samples_per_ms;
+
+        let mut joined_start = 0i64;
+        for region in &self.regions {
+            let joined_end = joined_start + region.len() as i64;
+            if position <= joined_end {
+                return (region.start as i64 + position - joined_start) / samples_per_ms;
+            }
+            joined_start = joined_end;
+        }
+
+        match self.regions.last() {
+            Some(region) => (region.end as i64 + position - joined_start) / samples_per_ms,
+            None => ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: usize = SAMPLE_RATE as usize;
+
+    // Deterministic low-level hiss so the noise floor is not -100 dB.
+    fn noise(len: usize, amplitude: f32) -> Vec<f32> {
+        let mut state = 12345u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                ((state >> 16) as f32 / 32768.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    fn tone(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    fn recording(parts: &[(bool, usize)]) -> Vec<f32> {
+        let mut samples = Vec::new();
+        for &(speech, len) in parts {
+            let hiss = noise(len, 0.001);
+            if speech {
+                samples.extend(tone(len).iter().zip(&hiss).map(|(t, n)| t + n));
+            } else {
+                samples.extend(hiss);
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn test_detect_speech_regions() {
+        let samples = recording(&[
+            (false, 2 * SECOND),
+            (true, SECOND),
+            (false, 3 * SECOND),
+            (true, 2 * SECOND),
+            (false, SECOND),
+        ]);
+        let regions = detect_speech(&samples, &VadOptions::default());
+
+        assert_eq!(regions.len(), 2);
+        let padding = ms_to_samples(200);
+        assert!(regions[0].start.abs_diff(2 * SECOND - padding) <= FRAME_LEN);
+        assert!(regions[0].end.abs_diff(3 * SECOND + padding) <= FRAME_LEN);
+        assert!(regions[1].start.abs_diff(6 * SECOND - padding) <= FRAME_LEN);
+        assert!(regions[1].end.abs_diff(8 * SECOND + padding) <= FRAME_LEN);
+    }
+
+    #[test]
+    fn test_detect_speech_merges_short_pauses() {
+        let samples = recording(&[
+            (false, SECOND),
+            (true, SECOND),
+            (false, SECOND / 4),
+            (true, SECOND),
+            (false, SECOND),
+        ]);
+        let regions = detect_speech(&samples, &VadOptions::default());
+        assert_eq!(regions.len(), 1);
+    }
+
+    #[test]
+    fn test_detect_speech_drops_blips() {
+        let samples = recording(&[(false, SECOND), (true, SECOND / 10), (false, SECOND)]);
+        assert!(detect_speech(&samples, &VadOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_detect_speech_silence() {
+        assert!(detect_speech(&vec![0.0; SECOND], &VadOptions::default()).is_empty());
+        assert!(detect_speech(&[], &VadOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_detect_speech_ignores_broadband_noise() {
+        let mut samples = noise(SECOND, 0.001);
+        samples.extend(noise(SECOND, 0.3));
+        samples.extend(noise(SECOND, 0.001));
+        assert!(detect_speech(&samples, &VadOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_speech_timeline_mapping() {
+        let samples = vec![0.0; 10 * SECOND];
+        let timeline =
+            SpeechTimeline::new(&samples, vec![SECOND..2 * SECOND, 5 * SECOND..7 * SECOND]);
+
+        assert_eq!(timeline.samples.len(), 3 * SECOND);
+        assert_eq!(timeline.speech_ms(), 3_000);
+        assert_eq!(timeline.to_original_ms(0), 1_000);
+        assert_eq!(timeline.to_original_ms(500), 1_500);
+        assert_eq!(timeline.to_original_ms(1_000), 2_000);
+        assert_eq!(timeline.to_original_ms(1_250), 5_250);
+        assert_eq!(timeline.to_original_ms(3_000), 7_000);
+        assert_eq!(timeline.to_original_ms(3_100), 7_100);
+    }
+
+    #[test]
+    fn test_speech_timeline_empty() {
+        let timeline = SpeechTimeline::new(&[0.0; 100], Vec::new());
+        assert!(timeline.is_empty());
+        assert!(timeline.samples.is_empty());
+        assert_eq!(timeline.to_original_ms(40), 40);
+    }
+}
@@ -0,0 +1,378 @@
+use crate::error::{Result, WhisperError};
+use std::ops::Range;
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_ALAW: u16 = 0x0006;
+const WAVE_FORMAT_MULAW: u16 = 0x0007;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+// Trailing 14 bytes shared by every KSDATAFORMAT_SUBTYPE_* GUID; the first
+// two bytes hold the plain format tag.
+const SUBFORMAT_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavEncoding {
+    Pcm,
+    Float,
+    ALaw,
+    MuLaw,
+    Other(u16),
+}
+
+impl WavEncoding {
+    fn from_tag(tag: u16) -> Self {
+        match tag {
+            WAVE_FORMAT_PCM => WavEncoding::Pcm,
+            WAVE_FORMAT_IEEE_FLOAT => WavEncoding::Float,
+            WAVE_FORMAT_ALAW => WavEncoding::ALaw,
+            WAVE_FORMAT_MULAW => WavEncoding::MuLaw,
+            other => WavEncoding::Other(other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WavHeader {
+    pub encoding: WavEncoding,
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub bits_per_sample: u16,
+    data: Range<usize>,
+}
+
+impl WavHeader {
+    /// Human-readable name of the sample encoding, used in error messages.
+    pub fn describe(&self) -> String {
+        match self.encoding {
+            WavEncoding::Pcm => format!("{}-bit integer PCM", self.bits_per_sample),
+            WavEncoding::Float => format!("{}-bit float", self.bits_per_sample),
+            WavEncoding::ALaw => format!("{}-bit A-law", self.bits_per_sample),
+            WavEncoding::MuLaw => format!("{}-bit µ-law", self.bits_per_sample),
+            WavEncoding::Other(tag) => match tag_name(tag) {
+                Some(name) => format!("{} (format tag 0x{:04X})", name, tag),
+                None => format!("format tag 0x{:04X}", tag),
+            },
+        }
+    }
+
+    fn is_supported(&self) -> bool {
+        match self.encoding {
+            WavEncoding::Pcm => matches!(self.bits_per_sample, 8 | 16 | 24 | 32),
+            WavEncoding::Float => matches!(self.bits_per_sample, 32 | 64),
+            WavEncoding::ALaw | WavEncoding::MuLaw => self.bits_per_sample == 8,
+            WavEncoding::Other(_) => false,
+        }
+    }
+}
+
+fn tag_name(tag: u16) -> Option<&'static str> {
+    match tag {
+        0x0002 => Some("Microsoft ADPCM"),
+        0x0011 => Some("IMA ADPCM"),
+        0x0031 => Some("GSM 6.10"),
+        0x0050 => Some("MPEG"),
+        0x0055 => Some("MPEG Layer 3"),
+        0x0160..=0x0163 => Some("Windows Media Audio"),
+        _ => None,
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+fn parse_fmt(chunk: &[u8]) -> Result<(WavEncoding, u16, u32, u16)> {
+    if chunk.len() < 16 {
+        return Err(WhisperError::AudioLoadFailed);
+    }
+
+    let mut tag = read_u16(chunk, 0);
+    let channels = read_u16(chunk, 2);
+    let sample_rate = read_u32(chunk, 4);
+    let bits_per_sample = read_u16(chunk, 14);
+
+    if tag == WAVE_FORMAT_EXTENSIBLE {
+        if chunk.len() < 40 || chunk[26..40] != SUBFORMAT_GUID_TAIL {
+            return Err(WhisperError::UnsupportedWavEncoding(
+                "WAVE_FORMAT_EXTENSIBLE with an unknown subformat".to_string(),
+            ));
+        }
+        tag = read_u16(chunk, 24);
+    }
+
+    Ok((
+        WavEncoding::from_tag(tag),
+        channels,
+        sample_rate,
+        bits_per_sample,
+    ))
+}
+
+/// Walks the RIFF chunks and returns the format and the location of the
+/// sample data. A data chunk whose size overruns the input (as written by
+/// streaming encoders) is clamped to the bytes actually present.
+pub fn parse_wav(data: &[u8]) -> Result<WavHeader> {
+    if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+        return Err(WhisperError::AudioLoadFailed);
+    }
+
+    let mut format = None;
+    let mut offset = 12;
+
+    while offset + 8 <= data.len() {
+        let id = &data[offset..offset + 4];
+        let size = read_u32(data, offset + 4) as usize;
+        let body_start = offset + 8;
+        let body_end = body_start.saturating_add(size).min(data.len());
+
+        if id == b"fmt " {
+            format = Some(parse_fmt(&data[body_start..body_end])?);
+        } else if id == b"data" {
+            let (encoding, channels, sample_rate, bits_per_sample) =
+                format.ok_or(WhisperError::AudioLoadFailed)?;
+            if channels == 0 || sample_rate == 0 {
+                return Err(WhisperError::AudioLoadFailed);
+            }
+            return Ok(WavHeader {
+                encoding,
+                channels,
+                sample_rate,
+                bits_per_sample,
+                data: body_start..body_end,
+            });
+        }
+
+        // chunks are padded to an even length
+        offset = body_start.saturating_add(size).saturating_add(size % 2);
+    }
+
+    Err(WhisperError::AudioLoadFailed)
+}
+
+/// Decodes the interleaved samples of a WAV file to f32 in [-1.0, 1.0].
+pub fn decode_wav(data: &[u8]) -> Result<(WavHeader, Vec<f32>)> {
+    let header = parse_wav(data)?;
+    if !header.is_supported() {
+        return Err(WhisperError::UnsupportedWavEncoding(header.describe()));
+    }
+
+    let sample_size = header.bits_per_sample as usize / 8;
+    let body = &data[header.data.clone()];
+    let body = &body[..body.len() - body.len() % sample_size];
+
+    let samples = match (header.encoding, header.bits_per_sample) {
+        (WavEncoding::Pcm, 8) => body.iter().map(|&b| (b as f32 - 128.0) / 128.0).collect(),
+        (WavEncoding::Pcm, bits) => body
+            .chunks_exact(sample_size)
+            .map(|bytes| decode_signed(bytes, bits))
+            .collect(),
+        (WavEncoding::Float, 32) => body
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        (WavEncoding::Float, _) => body
+            .chunks_exact(8)
+            .map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32)
+            .collect(),
+        (WavEncoding::ALaw, _) => body
+            .iter()
+            .map(|&b| alaw_to_linear(b) as f32 / 32768.0)
+            .collect(),
+        (WavEncoding::MuLaw, _) => body
+            .iter()
+            .map(|&b| mulaw_to_linear(b) as f32 / 32768.0)
+            .collect(),
+        (WavEncoding::Other(_), _) => unreachable!("rejected by is_supported"),
+    };
+
+    Ok((header, samples))
+}
+
+/// Little-endian signed integer of 2 to 4 bytes, scaled by its full range.
+fn decode_signed(bytes: &[u8], bits: u16) -> f32 {
+    let mut word = [0u8; 4];
+    word[4 - bytes.len()..].copy_from_slice(bytes);
+    let value = i32::from_le_bytes(word) >> (32 - bits);
+    (value as f64 / (1i64 << (bits - 1)) as f64) as f32
+}
+
+// G.711 expansion, following the reference implementation.
+fn alaw_to_linear(byte: u8) -> i16 {
+    let value = byte ^ 0x55;
+    let exponent = (value >> 4) & 0x07;
+    let mantissa = (value & 0x0F) as i16;
+    let magnitude = match exponent {
+        0 => (mantissa << 4) + 8,
+        _ => ((mantissa << 4) + 0x108) << (exponent - 1),
+    };
+    if value & 0x80 != 0 {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
+
+fn mulaw_to_linear(byte: u8) -> i16 {
+    let value = !byte;
+    let exponent = (value >> 4) & 0x07;
+    let mantissa = (value & 0x0F) as i16;
+    let magnitude = (((mantissa << 3) + 0x84) << exponent) - 0x84;
+    if value & 0x80 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_bytes(tag: u16, channels: u16, bits: u16, samples: &[u8]) -> Vec<u8> {
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&tag.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&8000u32.to_le_bytes());
+        let block_align = channels * bits / 8;
+        fmt.extend_from_slice(&(8000 * block_align as u32).to_le_bytes());
+        fmt.extend_from_slice(&block_align.to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+        build_riff(&fmt, samples)
+    }
+
+    fn build_riff(fmt: &[u8], samples: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"RIFF");
+        data.extend_from_slice(&((20 + fmt.len() + samples.len()) as u32).to_le_bytes());
+        data.extend_from_slice(b"WAVE");
+        data.extend_from_slice(b"fmt ");
+        data.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        data.extend_from_slice(fmt);
+        data.extend_from_slice(b"data");
+        data.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        data.extend_from_slice(samples);
+        data
+    }
+
+    #[test]
+    fn test_decode_pcm_8bit() {
+        let (header, samples) = decode_wav(&wav_bytes(1, 1, 8, &[0, 128, 192])).unwrap();
+        assert_eq!(header.sample_rate, 8000);
+        assert_eq!(samples, vec![-1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn test_decode_pcm_24bit() {
+        let data = wav_bytes(1, 1, 24, &[0x00, 0x00, 0x80, 0x00, 0x00, 0x40]);
+        let (_, samples) = decode_wav(&data).unwrap();
+        assert_eq!(samples, vec![-1.0, 0.5]);
+    }
+
+    #[test]
+    fn test_decode_pcm_32bit() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&i32::MIN.to_le_bytes());
+        body.extend_from_slice(&(1i32 << 30).to_le_bytes());
+        let (_, samples) = decode_wav(&wav_bytes(1, 2, 32, &body)).unwrap();
+        assert_eq!(samples, vec![-1.0, 0.5]);
+    }
+
+    #[test]
+    fn test_decode_float_64bit() {
+        let mut body = Vec::new();
+        body.extend_from_slice(&0.25f64.to_le_bytes());
+        body.extend_from_slice(&(-0.5f64).to_le_bytes());
+        let (header, samples) = decode_wav(&wav_bytes(3, 1, 64, &body)).unwrap();
+        assert_eq!(header.encoding, WavEncoding::Float);
+        assert_eq!(samples, vec![0.25, -0.5]);
+    }
+
+    #[test]
+    fn test_decode_alaw() {
+        let (_, samples) = decode_wav(&wav_bytes(6, 1, 8, &[0xD5, 0x55, 0xAA])).unwrap();
+        assert_eq!(samples[0], 8.0 / 32768.0);
+        assert_eq!(samples[1], -8.0 / 32768.0);
+        assert_eq!(samples[2], 32256.0 / 32768.0);
+    }
+
+    #[test]
+    fn test_decode_mulaw() {
+        let (_, samples) = decode_wav(&wav_bytes(7, 1, 8, &[0xFF, 0x00, 0x80])).unwrap();
+        assert_eq!(samples[0], 0.0);
+        assert_eq!(samples[1], -32124.0 / 32768.0);
+        assert_eq!(samples[2], 32124.0 / 32768.0);
+    }
+
+    #[test]
+    fn test_decode_extensible() {
+        let mut fmt = wav_bytes(WAVE_FORMAT_EXTENSIBLE, 1, 24, &[])[20..36].to_vec();
+        fmt.extend_from_slice(&22u16.to_le_bytes());
+        fmt.extend_from_slice(&24u16.to_le_bytes());
+        fmt.extend_from_slice(&0x4u32.to_le_bytes());
+        fmt.extend_from_slice(&WAVE_FORMAT_PCM.to_le_bytes());
+        fmt.extend_from_slice(&SUBFORMAT_GUID_TAIL);
+
+        let (header, samples) = decode_wav(&build_riff(&fmt, &[0x00, 0x00, 0x40])).unwrap();
+        assert_eq!(header.encoding, WavEncoding::Pcm);
+        assert_eq!(samples, vec![0.5]);
+    }
+
+    #[test]
+    fn test_decode_skips_unknown_chunks() {
+        let mut data = wav_bytes(1, 1, 16, &[0x00, 0x40]);
+        let list = [b"LIST".as_slice(), &3u32.to_le_bytes(), b"abc\0"].concat();
+        data.splice(12..12, list);
+        let (_, samples) = decode_wav(&data).unwrap();
+        assert_eq!(samples, vec![0.5]);
+    }
+
+    #[test]
+    fn test_decode_clamps_streaming_data_size() {
+        let mut data = wav_bytes(1, 1, 16, &[0x00, 0x40, 0x00, 0x40]);
+        let size_offset = data.len() - 8;
+        data[size_offset..size_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let (_, samples) = decode_wav(&data).unwrap();
+        assert_eq!(samples.len(), 2);
+    }
+
+    #[test]
+    fn test_decode_unsupported_encoding() {
+        let result = decode_wav(&wav_bytes(2, 1, 4, &[0; 16]));
+        match result {
+            Err(WhisperError::UnsupportedWavEncoding(name)) => {
+                assert_eq!(name, "Microsoft ADPCM (format tag 0x0002)");
+            }
+            other => panic!(
+                "Expected UnsupportedWavEncoding, got {:?}",
+                other.map(|_| ())
+            ),
+        }
+
+        let result = decode_wav(&wav_bytes(1, 1, 12, &[0; 16]));
+        assert!(matches!(
+            result,
+            Err(WhisperError::UnsupportedWavEncoding(name)) if name == "12-bit integer PCM"
+        ));
+    }
+
+    #[test]
+    fn test_parse_wav_invalid() {
+        assert!(matches!(
+            parse_wav(b"RIFF\x24\x00\x00\x00WAVE"),
+            Err(WhisperError::AudioLoadFailed)
+        ));
+        assert!(parse_wav(b"not a wav file").is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::audio::{load_audio_with_options, LoadOptions, SAMPLE_RATE};
+use crate::cli::{DetectLanguageArgs, DetectionFormat};
+use crate::error::{Result, WhisperError};
+use crate::log_error;
+use crate::log_info;
+use crate::log_success;
+use crate::model::{load_model_from_path, resolve_model_path};
+use crate::output::write_output;
+use serde::Serialize;
+use whisper_rs::WhisperContext;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageProbability {
+    pub code: String,
+    pub name: String,
+    pub probability: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageDetection {
+    pub source_file: String,
+    pub model_path: String,
+    pub languages: Vec<LanguageProbability>,
+}
+
+pub fn execute(args: &DetectLanguageArgs) -> Result<()> {
+    log_info!("Loading audio from {}...", args.file_name);
+    let load_options = LoadOptions {
+        threads: args.threads,
+        raw: args.raw.raw_format(),
+        channels: args.channels.channel_mix(),
+        resample_quality: args.resample_quality,
+        preprocess: args.preprocess.preprocess_options(),
+    };
+    let audio_data = load_audio_with_options(&args.file_name, &load_options)?;
+    let samples = audio_window(&audio_data.samples, args.offset, args.duration)?;
+
+    log_info!("Loading model...");
+    let model_path = resolve_model_path(args.model.as_deref(), None, true)?;
+    let context = load_model_from_path(&model_path, args.verify_model)?;
+
+    log_info!("Detecting language...");
+    let languages = detect_language(&context, samples, args.top_k, args.threads)?;
+
+    let detection = LanguageDetection {
+        source_file: args.file_name.clone(),
+        model_path: model_path.to_string_lossy().to_string(),
+        languages,
+    };
+    let output = format_detection(&detection, args.output_format)?;
+
+    write_output(&output, args.outfile.as_deref())?;
+    if let Some(outfile) = &args.outfile {
+        log_success!("Language detection saved to {}", outfile);
+    }
+
+    log_success!("Language detection complete");
+    Ok(())
+}
+
+/// Runs whisper's language identification on `samples` and returns the
+/// `top_k` most likely languages, most probable first.
+pub fn detect_language(
+    context: &WhisperContext,
+    samples: &[f32],
+    top_k: usize,
+    threads: usize,
+) -> Result<Vec<LanguageProbability>> {
+    if !context.is_multilingual() {
+        log_error!(
+            "Language detection requires a multilingual model, not an English-only .en model"
+        );
+        return Err(WhisperError::ModelNotMultilingual);
+    }
+
+    let mut state = context.create_state()?;
+    state.pcm_to_mel(samples, threads)?;
+    let (_, probabilities) = state.lang_detect(0, threads)?;
+
+    Ok(top_language_ids(&probabilities, top_k)
+        .into_iter()
+        .filter_map(|(id, probability)| {
+            Some(LanguageProbability {
+                code: whisper_rs::get_lang_str(id)?.to_string(),
+                name: whisper_rs::get_lang_str_full(id)?.to_string(),
+                probability,
+            })
+        })
+        .collect())
+}
+
+fn top_language_ids(probabilities: &[f32], top_k: usize) -> Vec<(i32, f32)> {
+    let mut ranked: Vec<(i32, f32)> = probabilities
+        .iter()
+        .enumerate()
+        .map(|(id, &probability)| (id as i32, probability))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(top_k);
+    ranked
+}
+
+fn audio_window(samples: &[f32], offset_secs: f32, duration_secs: f32) -> Result<&[f32]> {
+    let start = (offset_secs * SAMPLE_RATE as f32) as usize;
+    if start >= samples.len() {
+        return Err(WhisperError::Other(anyhow::anyhow!(
+            "Offset {}s is past the end of the audio",
+            offset_secs
+        )));
+    }
+
+    let length = (duration_secs * SAMPLE_RATE as f32) as usize;
+    let end = start.saturating_add(length).min(samples.len());
+    Ok(&samples[start..end])
+}
+
+fn format_detection(detection: &LanguageDetection, format: DetectionFormat) -> Result<String> {
+    match format {
+        DetectionFormat::Text => Ok(detection
+            .languages
+            .iter()
+            .map(|language| {
+                format!(
+                    "{}\t{:.4}\t{}",
+                    language.code, language.probability, language.name
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")),
+        DetectionFormat::Json => Ok(serde_json::to_string_pretty(detection)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_detection() -> LanguageDetection {
+        LanguageDetection {
+            source_file: "voicemail.wav".to_string(),
+            model_path: "/models/ggml-base.bin".to_string(),
+            languages: vec![
+                LanguageProbability {
+                    code: "de".to_string(),
+                    name: "german".to_string(),
+                    probability: 0.875,
+                },
+                LanguageProbability {
+                    code: "nl".to_string(),
+                    name: "dutch".to_string(),
+                    probability: 0.125,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_top_language_ids() {
+        let probabilities = [0.1, 0.6, 0.05, 0.25];
+        assert_eq!(
+            top_language_ids(&probabilities, 2),
+            vec![(1, 0.6), (3, 0.25)]
+        );
+    }
+
+    #[test]
+    fn test_top_language_ids_k_larger_than_languages() {
+        assert_eq!(top_language_ids(&[0.3, 0.7], 10).len(), 2);
+    }
+
+    #[test]
+    fn test_audio_window() {
+        let samples = vec![0.0; SAMPLE_RATE as usize * 10];
+        assert_eq!(
+            audio_window(&samples, 0.0, 30.0).unwrap().len(),
+            samples.len()
+        );
+        assert_eq!(
+            audio_window(&samples, 2.0, 3.0).unwrap().len(),
+            SAMPLE_RATE as usize * 3
+        );
+        assert_eq!(
+            audio_window(&samples, 8.0, 30.0).unwrap().len(),
+            SAMPLE_RATE as usize * 2
+        );
+    }
+
+    #[test]
+    fn test_audio_window_offset_past_end() {
+        let samples = vec![0.0; SAMPLE_RATE as usize];
+        assert!(audio_window(&samples, 5.0, 30.0).is_err());
+    }
+
+    #[test]
+    fn test_format_detection_text() {
+        let output = format_detection(&sample_detection(), DetectionFormat::Text).unwrap();
+        assert_eq!(output, "de\t0.8750\tgerman\nnl\t0.1250\tdutch");
+    }
+
+    #[test]
+    fn test_format_detection_json() {
+        let output = format_detection(&sample_detection(), DetectionFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["source_file"], "voicemail.wav");
+        assert_eq!(value["languages"][0]["code"], "de");
+        assert_eq!(value["languages"][1]["probability"], 0.125);
+    }
+}
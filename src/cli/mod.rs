@@ -1,6 +1,16 @@
+pub mod detect_language;
+pub mod models;
+pub mod prompt;
 pub mod transcribe;
+pub mod translate;
 
-use clap::{Parser, Subcommand};
+use crate::audio::{
+    ChannelMix, PcmEncoding, PreprocessFilter, PreprocessOptions, RawFormat, ResampleQuality,
+    VadOptions,
+};
+use crate::output::vtt::{parse_line_setting, parse_position_setting, CueAlign};
+use crate::output::OutputFormat;
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 #[command(name = "whisper-rs-cli")]
@@ -11,11 +21,17 @@ pub struct Cli {
 
     #[arg(short, long, global = true, action = clap::ArgAction::SetTrue)]
     pub debug: bool,
+
+    #[arg(long, global = true, action = clap::ArgAction::SetTrue)]
+    pub offline: bool,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     Transcribe(TranscribeArgs),
+    Translate(TranscribeArgs),
+    DetectLanguage(DetectLanguageArgs),
+    Models(ModelsArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -26,11 +42,459 @@ pub struct TranscribeArgs {
     #[arg(short, long, value_name = "MODEL")]
     pub model: Option<String>,
 
+    #[arg(long)]
+    pub verify_model: bool,
+
     #[arg(short, long, value_name = "LANG")]
     pub language: Option<String>,
 
     #[arg(short, long, value_name = "OUTFILE")]
     pub outfile: Option<String>,
+
+    #[arg(short = 'f', long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output_format: OutputFormat,
+
+    #[arg(
+        long,
+        value_name = "LINE",
+        // negative line numbers count from the bottom, as in "-1,end"
+        allow_hyphen_values = true,
+        value_parser = parse_line_setting
+    )]
+    pub vtt_line: Option<String>,
+
+    #[arg(long, value_name = "POSITION", value_parser = parse_position_setting)]
+    pub vtt_position: Option<String>,
+
+    #[arg(long, value_name = "ALIGN", value_enum)]
+    pub vtt_align: Option<CueAlign>,
+
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub word_timestamps: bool,
+
+    #[arg(long, value_name = "CHARS", requires = "word_timestamps")]
+    pub max_len: Option<u16>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "word_timestamps",
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub words_per_cue: Option<u16>,
+
+    #[command(flatten)]
+    pub sampling: SamplingArgs,
+
+    #[arg(long, value_name = "TEXT", conflicts_with = "prompt_file")]
+    pub prompt: Option<String>,
+
+    #[arg(long, value_name = "PATH")]
+    pub prompt_file: Option<String>,
+
+    #[arg(long, value_name = "PATH")]
+    pub glossary: Option<String>,
+
+    #[command(flatten)]
+    pub raw: RawAudioArgs,
+
+    #[command(flatten)]
+    pub channels: ChannelArgs,
+
+    #[arg(long, value_enum, default_value_t = ResampleQuality::Best)]
+    pub resample_quality: ResampleQuality,
+
+    #[command(flatten)]
+    pub preprocess: PreprocessArgs,
+
+    #[arg(long, conflicts_with_all = ["channel", "downmix_weights"])]
+    pub split_channels: bool,
+
+    #[command(flatten)]
+    pub vad: VadArgs,
+
+    #[arg(
+        long,
+        value_name = "LABEL,LABEL,...",
+        value_delimiter = ',',
+        requires = "split_channels"
+    )]
+    pub channel_labels: Option<Vec<String>>,
+
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        default_value_t = default_threads(),
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub threads: usize,
+}
+
+/// Number of CPUs this process may actually use. The standard library takes
+/// the affinity mask and cgroup CPU quotas into account, so containers limited
+/// to a few cores don't get oversubscribed.
+pub fn default_threads() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+/// Layout of headerless PCM input. Only used when `--raw-format` is given.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct RawAudioArgs {
+    #[arg(long, value_enum, value_name = "ENCODING", requires = "raw_rate")]
+    pub raw_format: Option<PcmEncoding>,
+
+    #[arg(
+        long,
+        value_name = "HZ",
+        requires = "raw_format",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub raw_rate: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        requires = "raw_format",
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub raw_channels: u16,
+}
+
+impl RawAudioArgs {
+    pub fn raw_format(&self) -> Option<RawFormat> {
+        Some(RawFormat {
+            encoding: self.raw_format?,
+            sample_rate: self.raw_rate?,
+            channels: self.raw_channels,
+        })
+    }
+}
+
+/// Which channel(s) of multichannel audio to transcribe.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct ChannelArgs {
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with = "downmix_weights",
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub channel: Option<u16>,
+
+    #[arg(long, value_name = "W,W,...", value_delimiter = ',', value_parser = parse_weight)]
+    pub downmix_weights: Option<Vec<f32>>,
+}
+
+impl ChannelArgs {
+    pub fn channel_mix(&self) -> ChannelMix {
+        match (self.channel, &self.downmix_weights) {
+            (Some(channel), _) => ChannelMix::Select(channel - 1),
+            (None, Some(weights)) => ChannelMix::Weighted(weights.clone()),
+            (None, None) => ChannelMix::Downmix,
+        }
+    }
+}
+
+/// Voice activity detection: only transcribe the parts that contain speech.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct VadArgs {
+    #[arg(long)]
+    pub vad: bool,
+
+    #[arg(
+        long,
+        value_name = "DB",
+        default_value_t = VadOptions::default().threshold_db,
+        requires = "vad",
+        value_parser = parse_positive
+    )]
+    pub vad_threshold: f32,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = VadOptions::default().min_silence_ms,
+        requires = "vad"
+    )]
+    pub vad_min_silence: u32,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = VadOptions::default().padding_ms,
+        requires = "vad"
+    )]
+    pub vad_padding: u32,
+}
+
+impl VadArgs {
+    pub fn vad_options(&self) -> Option<VadOptions> {
+        self.vad.then(|| VadOptions {
+            threshold_db: self.vad_threshold,
+            min_silence_ms: self.vad_min_silence,
+            padding_ms: self.vad_padding,
+            ..Default::default()
+        })
+    }
+}
+
+/// Filters run on the 16 kHz audio before inference.
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct PreprocessArgs {
+    #[arg(long, value_enum, value_name = "FILTER,...", value_delimiter = ',')]
+    pub preprocess: Vec<PreprocessFilter>,
+
+    #[arg(
+        long,
+        value_name = "HZ",
+        default_value_t = 80.0,
+        requires = "preprocess",
+        value_parser = parse_cutoff
+    )]
+    pub highpass_cutoff: f32,
+
+    #[arg(
+        long,
+        value_name = "DBFS",
+        default_value_t = -50.0,
+        allow_negative_numbers = true,
+        requires = "preprocess",
+        value_parser = parse_dbfs
+    )]
+    pub gate_threshold: f32,
+
+    #[arg(
+        long,
+        value_name = "DBFS",
+        default_value_t = -1.0,
+        allow_negative_numbers = true,
+        requires = "preprocess",
+        value_parser = parse_dbfs
+    )]
+    pub peak_level: f32,
+
+    #[arg(
+        long,
+        value_name = "DBFS",
+        default_value_t = -20.0,
+        allow_negative_numbers = true,
+        requires = "preprocess",
+        value_parser = parse_dbfs
+    )]
+    pub rms_level: f32,
+}
+
+impl PreprocessArgs {
+    pub fn preprocess_options(&self) -> PreprocessOptions {
+        let enabled = |filter| self.preprocess.contains(&filter);
+        PreprocessOptions {
+            remove_dc: enabled(PreprocessFilter::Dc),
+            highpass_hz: enabled(PreprocessFilter::Highpass).then_some(self.highpass_cutoff),
+            gate_threshold_db: enabled(PreprocessFilter::Gate).then_some(self.gate_threshold),
+            peak_db: enabled(PreprocessFilter::Peak).then_some(self.peak_level),
+            rms_db: enabled(PreprocessFilter::Rms).then_some(self.rms_level),
+        }
+    }
+}
+
+fn parse_cutoff(value: &str) -> Result<f32, String> {
+    let number: f32 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    // must stay below the Nyquist frequency of the 16 kHz audio
+    if number > 0.0 && number < 8000.0 {
+        Ok(number)
+    } else {
+        Err(format!("{} is not between 0 and 8000 Hz", number))
+    }
+}
+
+fn parse_dbfs(value: &str) -> Result<f32, String> {
+    let number: f32 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if number <= 0.0 {
+        Ok(number)
+    } else {
+        Err(format!("{} dBFS is above full scale", number))
+    }
+}
+
+fn parse_weight(value: &str) -> Result<f32, String> {
+    let number: f32 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if number >= 0.0 {
+        Ok(number)
+    } else {
+        Err(format!("{} must not be negative", number))
+    }
+}
+
+// whisper.cpp runs at most 8 decoders in parallel
+const MAX_DECODERS: i64 = 8;
+
+#[derive(Args, Debug, Clone, PartialEq)]
+pub struct SamplingArgs {
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u8).range(1..=MAX_DECODERS)
+    )]
+    pub beam_size: Option<u8>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 5,
+        conflicts_with = "beam_size",
+        value_parser = clap::value_parser!(u8).range(1..=MAX_DECODERS)
+    )]
+    pub best_of: u8,
+
+    #[arg(long, value_name = "T", default_value_t = 0.0, value_parser = parse_unit_interval)]
+    pub temperature: f32,
+
+    #[arg(long, value_name = "T", default_value_t = 0.2, value_parser = parse_unit_interval)]
+    pub temperature_increment: f32,
+
+    #[arg(long, value_name = "THRESHOLD", default_value_t = 2.4, value_parser = parse_positive)]
+    pub entropy_threshold: f32,
+
+    #[arg(long, value_name = "THRESHOLD", default_value_t = -1.0, allow_negative_numbers = true)]
+    pub logprob_threshold: f32,
+
+    #[arg(long, value_name = "THRESHOLD", default_value_t = 0.6, value_parser = parse_unit_interval)]
+    pub no_speech_threshold: f32,
+}
+
+fn parse_unit_interval(value: &str) -> Result<f32, String> {
+    let number: f32 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if (0.0..=1.0).contains(&number) {
+        Ok(number)
+    } else {
+        Err(format!("{} is not between 0.0 and 1.0", number))
+    }
+}
+
+fn parse_non_negative(value: &str) -> Result<f32, String> {
+    let number: f32 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if number >= 0.0 {
+        Ok(number)
+    } else {
+        Err(format!("{} must not be negative", number))
+    }
+}
+
+fn parse_positive(value: &str) -> Result<f32, String> {
+    let number: f32 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if number > 0.0 {
+        Ok(number)
+    } else {
+        Err(format!("{} must be greater than 0", number))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum DetectionFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Parser, Debug)]
+pub struct DetectLanguageArgs {
+    #[arg(value_name = "FILE")]
+    pub file_name: String,
+
+    #[arg(short, long, value_name = "MODEL")]
+    pub model: Option<String>,
+
+    #[arg(long)]
+    pub verify_model: bool,
+
+    #[arg(long, value_name = "SECONDS", default_value_t = 0.0, value_parser = parse_non_negative)]
+    pub offset: f32,
+
+    #[arg(long, value_name = "SECONDS", default_value_t = 30.0, value_parser = parse_positive)]
+    pub duration: f32,
+
+    #[arg(
+        short = 'k',
+        long,
+        value_name = "K",
+        default_value_t = 5,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub top_k: usize,
+
+    #[arg(short = 'f', long, value_enum, default_value_t = DetectionFormat::Text)]
+    pub output_format: DetectionFormat,
+
+    #[arg(short, long, value_name = "OUTFILE")]
+    pub outfile: Option<String>,
+
+    #[command(flatten)]
+    pub raw: RawAudioArgs,
+
+    #[command(flatten)]
+    pub channels: ChannelArgs,
+
+    #[arg(long, value_enum, default_value_t = ResampleQuality::Best)]
+    pub resample_quality: ResampleQuality,
+
+    #[command(flatten)]
+    pub preprocess: PreprocessArgs,
+
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        default_value_t = default_threads(),
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub threads: usize,
+}
+
+#[derive(Parser, Debug)]
+pub struct ModelsArgs {
+    #[command(subcommand)]
+    pub command: ModelsCommand,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum ModelsCommand {
+    /// List installed models and the models available to download
+    List,
+    /// Download a model into ~/.local/share/whisper
+    Pull {
+        #[arg(value_name = "MODEL")]
+        name: String,
+
+        /// Download again even if the model is already installed
+        #[arg(long)]
+        force: bool,
+    },
+    /// Delete every installed copy of a model
+    Rm {
+        #[arg(value_name = "MODEL")]
+        name: String,
+    },
+    /// Show the size, type and quantization of an installed model
+    Info {
+        #[arg(value_name = "MODEL")]
+        name: String,
+    },
 }
 
 #[cfg(test)]
@@ -45,6 +509,77 @@ mod tests {
         assert!(matches!(cli.command, Commands::Transcribe(_)));
     }
 
+    #[test]
+    fn test_cli_parse_translate() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "translate",
+            "voicemail.ogg",
+            "--model",
+            "small",
+            "--outfile",
+            "voicemail.txt",
+        ]);
+        let Commands::Translate(translate_args) = args.unwrap().command else {
+            panic!("Expected Translate command");
+        };
+        assert_eq!(translate_args.file_name, "voicemail.ogg");
+        assert_eq!(translate_args.model, Some("small".to_string()));
+        assert_eq!(translate_args.outfile, Some("voicemail.txt".to_string()));
+    }
+
+    #[test]
+    fn test_cli_parse_translate_missing_file() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "translate"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_detect_language_defaults() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "detect-language", "call.wav"]);
+        let Commands::DetectLanguage(detect_args) = args.unwrap().command else {
+            panic!("Expected DetectLanguage command");
+        };
+        assert_eq!(detect_args.file_name, "call.wav");
+        assert_eq!(detect_args.offset, 0.0);
+        assert_eq!(detect_args.duration, 30.0);
+        assert_eq!(detect_args.top_k, 5);
+        assert_eq!(detect_args.output_format, DetectionFormat::Text);
+    }
+
+    #[test]
+    fn test_cli_parse_detect_language_options() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "detect-language",
+            "call.wav",
+            "--offset",
+            "12.5",
+            "--duration",
+            "10",
+            "--top-k",
+            "3",
+            "--output-format",
+            "json",
+        ]);
+        let Commands::DetectLanguage(detect_args) = args.unwrap().command else {
+            panic!("Expected DetectLanguage command");
+        };
+        assert_eq!(detect_args.offset, 12.5);
+        assert_eq!(detect_args.duration, 10.0);
+        assert_eq!(detect_args.top_k, 3);
+        assert_eq!(detect_args.output_format, DetectionFormat::Json);
+    }
+
+    #[test]
+    fn test_cli_parse_detect_language_invalid_window() {
+        for extra in ["--offset=-5", "--duration=0", "--duration=-10", "--top-k=0"] {
+            let args =
+                Cli::try_parse_from(["whisper-rs-cli", "detect-language", "call.wav", extra]);
+            assert!(args.is_err(), "{} should be rejected", extra);
+        }
+    }
+
     #[test]
     fn test_cli_parse_with_file() {
         let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "audio.mp3"]);
@@ -107,6 +642,681 @@ mod tests {
         assert_eq!(transcribe_args.outfile, Some("output.txt".to_string()));
     }
 
+    #[test]
+    fn test_cli_parse_output_format_default() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.output_format, OutputFormat::Text);
+    }
+
+    #[test]
+    fn test_cli_parse_output_format_srt() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--output-format",
+            "srt",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.output_format, OutputFormat::Srt);
+    }
+
+    #[test]
+    fn test_cli_parse_output_format_json() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav", "-f", "json"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.output_format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn test_cli_parse_vtt_cue_settings() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--output-format",
+            "vtt",
+            "--vtt-line",
+            "90%",
+            "--vtt-position",
+            "50%",
+            "--vtt-align",
+            "center",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.output_format, OutputFormat::Vtt);
+        assert_eq!(transcribe_args.vtt_line, Some("90%".to_string()));
+        assert_eq!(transcribe_args.vtt_position, Some("50%".to_string()));
+        assert_eq!(transcribe_args.vtt_align, Some(CueAlign::Center));
+    }
+
+    #[test]
+    fn test_cli_parse_negative_vtt_line() {
+        for line in ["-1", "-1,end"] {
+            let args = Cli::try_parse_from([
+                "whisper-rs-cli",
+                "transcribe",
+                "test.wav",
+                "--vtt-line",
+                line,
+            ]);
+            let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+                panic!("Expected Transcribe command");
+            };
+            assert_eq!(transcribe_args.vtt_line, Some(line.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_invalid_vtt_line() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--vtt-line",
+            "bottom",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_word_timestamps() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--word-timestamps",
+            "--max-len",
+            "40",
+            "--words-per-cue",
+            "3",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert!(transcribe_args.word_timestamps);
+        assert_eq!(transcribe_args.max_len, Some(40));
+        assert_eq!(transcribe_args.words_per_cue, Some(3));
+    }
+
+    #[test]
+    fn test_cli_parse_words_per_cue_requires_word_timestamps() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--words-per-cue",
+            "2",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_words_per_cue_zero() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--word-timestamps",
+            "--words-per-cue",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_sampling_defaults() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        let sampling = transcribe_args.sampling;
+        assert_eq!(sampling.beam_size, None);
+        assert_eq!(sampling.best_of, 5);
+        assert_eq!(sampling.temperature, 0.0);
+        assert_eq!(sampling.temperature_increment, 0.2);
+        assert_eq!(sampling.entropy_threshold, 2.4);
+        assert_eq!(sampling.logprob_threshold, -1.0);
+        assert_eq!(sampling.no_speech_threshold, 0.6);
+    }
+
+    #[test]
+    fn test_cli_parse_sampling_options() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--beam-size",
+            "5",
+            "--temperature",
+            "0.1",
+            "--temperature-increment",
+            "0",
+            "--entropy-threshold",
+            "2.8",
+            "--logprob-threshold",
+            "-0.5",
+            "--no-speech-threshold",
+            "0.4",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        let sampling = transcribe_args.sampling;
+        assert_eq!(sampling.beam_size, Some(5));
+        assert_eq!(sampling.temperature, 0.1);
+        assert_eq!(sampling.temperature_increment, 0.0);
+        assert_eq!(sampling.entropy_threshold, 2.8);
+        assert_eq!(sampling.logprob_threshold, -0.5);
+        assert_eq!(sampling.no_speech_threshold, 0.4);
+    }
+
+    #[test]
+    fn test_cli_parse_best_of() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "a.wav", "--best-of", "3"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.sampling.best_of, 3);
+
+        // beam search has no best-of setting, so the combination is rejected
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "a.wav",
+            "--beam-size",
+            "5",
+            "--best-of",
+            "3",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_sampling_out_of_range() {
+        for (flag, value) in [
+            ("--beam-size", "0"),
+            ("--beam-size", "9"),
+            ("--best-of", "0"),
+            ("--temperature", "1.5"),
+            ("--temperature-increment", "-0.2"),
+            ("--entropy-threshold", "0"),
+            ("--no-speech-threshold", "2"),
+        ] {
+            let args =
+                Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav", flag, value]);
+            assert!(args.is_err(), "{} {} should be rejected", flag, value);
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_prompt_and_glossary() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--prompt",
+            "Sprint planning.",
+            "--glossary",
+            "terms.txt",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.prompt, Some("Sprint planning.".to_string()));
+        assert_eq!(transcribe_args.glossary, Some("terms.txt".to_string()));
+    }
+
+    #[test]
+    fn test_cli_parse_prompt_conflicts_with_prompt_file() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--prompt",
+            "text",
+            "--prompt-file",
+            "prompt.txt",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_threads_default() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.threads, default_threads());
+        assert!(transcribe_args.threads >= 1);
+    }
+
+    #[test]
+    fn test_cli_parse_threads() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav", "-t", "32"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.threads, 32);
+    }
+
+    #[test]
+    fn test_cli_parse_threads_zero() {
+        let args =
+            Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav", "--threads", "0"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_raw_format() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "-",
+            "--raw-format",
+            "s16le",
+            "--raw-rate",
+            "8000",
+            "--raw-channels",
+            "2",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(
+            transcribe_args.raw.raw_format(),
+            Some(RawFormat {
+                encoding: PcmEncoding::S16le,
+                sample_rate: 8000,
+                channels: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_raw_format_default() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.raw.raw_format(), None);
+        assert_eq!(transcribe_args.raw.raw_channels, 1);
+    }
+
+    #[test]
+    fn test_cli_parse_raw_format_requires_rate() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.pcm",
+            "--raw-format",
+            "f32le",
+        ]);
+        assert!(args.is_err());
+
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.pcm",
+            "--raw-rate",
+            "8000",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_raw_channels_requires_format() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--raw-channels",
+            "2",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_raw_format_invalid() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.pcm",
+            "--raw-format",
+            "s32be",
+            "--raw-rate",
+            "8000",
+        ]);
+        assert!(args.is_err());
+
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "detect-language",
+            "test.pcm",
+            "--raw-format",
+            "u8",
+            "--raw-rate",
+            "8000",
+            "--raw-channels",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_channel() {
+        let args =
+            Cli::try_parse_from(["whisper-rs-cli", "transcribe", "room.wav", "--channel", "3"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(
+            transcribe_args.channels.channel_mix(),
+            ChannelMix::Select(2)
+        );
+
+        let args =
+            Cli::try_parse_from(["whisper-rs-cli", "transcribe", "room.wav", "--channel", "0"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_downmix_weights() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "detect-language",
+            "movie.wav",
+            "--downmix-weights",
+            "1,1,2,0,0.5,0.5",
+        ]);
+        let Commands::DetectLanguage(detect_args) = args.unwrap().command else {
+            panic!("Expected DetectLanguage command");
+        };
+        assert_eq!(
+            detect_args.channels.channel_mix(),
+            ChannelMix::Weighted(vec![1.0, 1.0, 2.0, 0.0, 0.5, 0.5])
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_channel_default_and_conflicts() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "room.wav"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.channels.channel_mix(), ChannelMix::Downmix);
+
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "room.wav",
+            "--channel",
+            "1",
+            "--downmix-weights",
+            "1,1",
+        ]);
+        assert!(args.is_err());
+
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "room.wav",
+            "--downmix-weights",
+            "1,x",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_split_channels() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "call.wav",
+            "--split-channels",
+            "--channel-labels",
+            "Agent,Customer",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert!(transcribe_args.split_channels);
+        assert_eq!(
+            transcribe_args.channel_labels,
+            Some(vec!["Agent".to_string(), "Customer".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_split_channels_conflicts() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "call.wav",
+            "--channel-labels",
+            "Agent,Customer",
+        ]);
+        assert!(args.is_err());
+
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "call.wav",
+            "--split-channels",
+            "--channel",
+            "1",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_resample_quality() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.resample_quality, ResampleQuality::Best);
+
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--resample-quality",
+            "fast",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.resample_quality, ResampleQuality::Fast);
+
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--resample-quality",
+            "perfect",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_vad() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "meeting.wav"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(transcribe_args.vad.vad_options(), None);
+
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "meeting.wav",
+            "--vad",
+            "--vad-threshold",
+            "15",
+            "--vad-min-silence",
+            "1000",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        let options = transcribe_args.vad.vad_options().unwrap();
+        assert_eq!(options.threshold_db, 15.0);
+        assert_eq!(options.min_silence_ms, 1000);
+        assert_eq!(options.padding_ms, VadOptions::default().padding_ms);
+    }
+
+    #[test]
+    fn test_cli_parse_vad_options_require_vad() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "meeting.wav",
+            "--vad-threshold",
+            "15",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_offline() {
+        let cli = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "a.wav", "--offline"]);
+        assert!(cli.unwrap().offline);
+
+        let cli = Cli::try_parse_from(["whisper-rs-cli", "--offline", "models", "pull", "base"]);
+        assert!(cli.unwrap().offline);
+
+        let cli = Cli::try_parse_from(["whisper-rs-cli", "models", "list"]);
+        assert!(!cli.unwrap().offline);
+    }
+
+    #[test]
+    fn test_cli_parse_models() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "models", "list"]);
+        let Commands::Models(models_args) = args.unwrap().command else {
+            panic!("Expected Models command");
+        };
+        assert_eq!(models_args.command, ModelsCommand::List);
+
+        let args = Cli::try_parse_from(["whisper-rs-cli", "models", "pull", "small", "--force"]);
+        let Commands::Models(models_args) = args.unwrap().command else {
+            panic!("Expected Models command");
+        };
+        assert_eq!(
+            models_args.command,
+            ModelsCommand::Pull {
+                name: "small".to_string(),
+                force: true,
+            }
+        );
+
+        assert!(Cli::try_parse_from(["whisper-rs-cli", "models", "rm"]).is_err());
+        assert!(Cli::try_parse_from(["whisper-rs-cli", "models"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_verify_model() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "a.wav", "--verify-model"]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert!(transcribe_args.verify_model);
+
+        let args = Cli::try_parse_from(["whisper-rs-cli", "detect-language", "a.wav"]);
+        let Commands::DetectLanguage(detect_args) = args.unwrap().command else {
+            panic!("Expected DetectLanguage command");
+        };
+        assert!(!detect_args.verify_model);
+    }
+
+    #[test]
+    fn test_cli_parse_preprocess() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "call.wav",
+            "--preprocess",
+            "dc,highpass,rms",
+            "--highpass-cutoff",
+            "120",
+            "--rms-level",
+            "-18",
+        ]);
+        let Commands::Transcribe(transcribe_args) = args.unwrap().command else {
+            panic!("Expected Transcribe command");
+        };
+        assert_eq!(
+            transcribe_args.preprocess.preprocess_options(),
+            PreprocessOptions {
+                remove_dc: true,
+                highpass_hz: Some(120.0),
+                rms_db: Some(-18.0),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_cli_parse_preprocess_default() {
+        let args = Cli::try_parse_from(["whisper-rs-cli", "detect-language", "call.wav"]);
+        let Commands::DetectLanguage(detect_args) = args.unwrap().command else {
+            panic!("Expected DetectLanguage command");
+        };
+        assert!(detect_args.preprocess.preprocess_options().is_empty());
+    }
+
+    #[test]
+    fn test_cli_parse_preprocess_levels_require_preprocess() {
+        for extra in [
+            ["--highpass-cutoff", "120"],
+            ["--gate-threshold", "-40"],
+            ["--peak-level", "-3"],
+            ["--rms-level", "-18"],
+        ] {
+            let mut argv = vec!["whisper-rs-cli", "transcribe", "call.wav"];
+            argv.extend(extra);
+            assert!(
+                Cli::try_parse_from(argv).is_err(),
+                "{:?} should be rejected",
+                extra
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_preprocess_invalid() {
+        for extra in [
+            ["--preprocess", "reverb"],
+            ["--preprocess=highpass", "--highpass-cutoff=9000"],
+            ["--preprocess=peak", "--peak-level=3"],
+        ] {
+            let mut argv = vec!["whisper-rs-cli", "transcribe", "call.wav"];
+            argv.extend(extra);
+            assert!(Cli::try_parse_from(argv).is_err());
+        }
+    }
+
+    #[test]
+    fn test_cli_parse_invalid_output_format() {
+        let args = Cli::try_parse_from([
+            "whisper-rs-cli",
+            "transcribe",
+            "test.wav",
+            "--output-format",
+            "docx",
+        ]);
+        assert!(args.is_err());
+    }
+
     #[test]
     fn test_cli_parse_debug_flag() {
         let args = Cli::try_parse_from(["whisper-rs-cli", "transcribe", "test.wav", "--debug"]);
@@ -0,0 +1,186 @@
+use crate::cli::{ModelsArgs, ModelsCommand};
+use crate::error::{Result, WhisperError};
+use crate::log_error;
+use crate::model::downloader::{download_model, partial_path};
+use crate::model::{
+    explicit_model_path, find_installed_models, get_model_search_dirs, get_models_dir,
+    known_models, list_installed_models, read_model_header, InstalledModel, ModelHeader,
+};
+use crate::output::write_output;
+use indicatif::HumanBytes;
+use std::fs;
+use std::path::Path;
+
+pub fn execute(args: &ModelsArgs) -> Result<()> {
+    match &args.command {
+        ModelsCommand::List => list(),
+        ModelsCommand::Pull { name, force } => pull(name, *force),
+        ModelsCommand::Rm { name } => remove(name),
+        ModelsCommand::Info { name } => info(name),
+    }
+}
+
+fn list() -> Result<()> {
+    let output = format_model_list(&list_installed_models(), &known_models());
+    write_output(&output, None)
+}
+
+fn pull(name: &str, force: bool) -> Result<()> {
+    let model_path = get_models_dir()?.join(format!("ggml-{}.bin", name));
+    if model_path.exists() && !force {
+        return write_output(
+            &format!("{} is already installed at {}", name, model_path.display()),
+            None,
+        );
+    }
+
+    download_model(name, &model_path)?;
+    write_output(
+        &format!("Downloaded {} to {}", name, model_path.display()),
+        None,
+    )
+}
+
+fn remove(name: &str) -> Result<()> {
+    let mut paths = find_installed_models(name);
+    paths.extend(
+        get_model_search_dirs()
+            .iter()
+            .map(|models_dir| partial_path(&models_dir.join(format!("ggml-{}.bin", name))))
+            .filter(|path| path.is_file()),
+    );
+    if paths.is_empty() {
+        log_error!("Model '{}' is not installed", name);
+        return Err(WhisperError::ModelNotFound);
+    }
+
+    let mut output = String::new();
+    for path in &paths {
+        fs::remove_file(path)?;
+        output.push_str(&format!("Removed {}\n", path.display()));
+    }
+    write_output(&output, None)
+}
+
+fn info(name: &str) -> Result<()> {
+    let model_path = match explicit_model_path(name)? {
+        Some(model_path) => Some(model_path),
+        None => find_installed_models(name).into_iter().next(),
+    };
+    let Some(model_path) = model_path else {
+        log_error!("Model '{}' is not installed", name);
+        return Err(WhisperError::ModelNotFound);
+    };
+
+    let header = read_model_header(&model_path)?;
+    let size = fs::metadata(&model_path)?.len();
+    write_output(&format_model_info(&model_path, size, &header), None)
+}
+
+fn format_model_list(installed: &[InstalledModel], known: &[&str]) -> String {
+    let mut output = String::from("Installed:\n");
+    if installed.is_empty() {
+        output.push_str("  (none)\n");
+    }
+    let width = installed.iter().map(|m| m.name.len()).max().unwrap_or(0);
+    for model in installed {
+        output.push_str(&format!(
+            "  {:width$}  {:>10}  {}\n",
+            model.name,
+            HumanBytes(model.size).to_string(),
+            model.path.display(),
+        ));
+    }
+
+    let downloadable: Vec<&str> = known
+        .iter()
+        .copied()
+        .filter(|name| !installed.iter().any(|model| model.name == *name))
+        .collect();
+    if !downloadable.is_empty() {
+        output.push_str("\nAvailable to download:\n");
+        for name in downloadable {
+            output.push_str(&format!("  {}\n", name));
+        }
+    }
+
+    output
+}
+
+fn format_model_info(path: &Path, size: u64, header: &ModelHeader) -> String {
+    let rows = [
+        ("path", path.display().to_string()),
+        ("size", HumanBytes(size).to_string()),
+        ("type", header.model_type().to_string()),
+        (
+            "multilingual",
+            if header.is_multilingual() {
+                "yes"
+            } else {
+                "no"
+            }
+            .to_string(),
+        ),
+        ("vocab size", header.n_vocab.to_string()),
+        ("audio layers", header.n_audio_layer.to_string()),
+        ("text layers", header.n_text_layer.to_string()),
+        ("state size", header.n_audio_state.to_string()),
+        ("mel bands", header.n_mels.to_string()),
+        ("quantization", header.quantization().to_string()),
+    ];
+
+    rows.iter()
+        .map(|(key, value)| format!("{:<13} {}\n", format!("{}:", key), value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::header::base_header;
+    use std::path::PathBuf;
+
+    fn installed(name: &str, size: u64) -> InstalledModel {
+        InstalledModel {
+            name: name.to_string(),
+            path: PathBuf::from(format!("/models/ggml-{}.bin", name)),
+            size,
+        }
+    }
+
+    #[test]
+    fn test_format_model_list() {
+        let output = format_model_list(
+            &[installed("base", 147_951_465), installed("custom", 1024)],
+            &["tiny", "base"],
+        );
+        assert_eq!(
+            output,
+            "Installed:\n  base    141.10 MiB  /models/ggml-base.bin\n  custom    1.00 KiB  /models/ggml-custom.bin\n\nAvailable to download:\n  tiny\n"
+        );
+    }
+
+    #[test]
+    fn test_format_model_list_empty() {
+        let output = format_model_list(&[], &["tiny"]);
+        assert_eq!(
+            output,
+            "Installed:\n  (none)\n\nAvailable to download:\n  tiny\n"
+        );
+    }
+
+    #[test]
+    fn test_format_model_info() {
+        let output = format_model_info(
+            Path::new("/models/ggml-base.bin"),
+            147_951_465,
+            &base_header(),
+        );
+        assert!(output.starts_with("path:         /models/ggml-base.bin\n"));
+        assert!(output.contains("type:         base\n"));
+        assert!(output.contains("multilingual: yes\n"));
+        assert!(output.contains("vocab size:   51865\n"));
+        assert!(output.contains("audio layers: 6\n"));
+        assert!(output.contains("quantization: f16\n"));
+    }
+}
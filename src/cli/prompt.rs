@@ -0,0 +1,154 @@
+use crate::error::Result;
+use crate::log_warning;
+use std::fs;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitialPrompt {
+    pub text: String,
+    pub dropped_terms: usize,
+}
+
+/// Reads a glossary file: one term per line, blank lines and `#` comments
+/// are ignored.
+pub fn load_glossary(path: &str) -> Result<Vec<String>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(parse_glossary(&contents))
+}
+
+fn parse_glossary(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Combines the prompt and glossary terms into one initial prompt, adding
+/// terms in order until `max_tokens` is reached as measured by `count_tokens`.
+pub fn build_initial_prompt<F>(
+    prompt: Option<&str>,
+    glossary: &[String],
+    max_tokens: usize,
+    count_tokens: F,
+) -> Result<InitialPrompt>
+where
+    F: Fn(&str) -> Result<usize>,
+{
+    // whisper.cpp takes the prompt as a C string
+    let prompt = prompt
+        .unwrap_or_default()
+        .replace('\0', "")
+        .trim()
+        .to_string();
+
+    if count_tokens(&prompt)? > max_tokens {
+        log_warning!(
+            "Prompt exceeds {} tokens, whisper will only use its final tokens",
+            max_tokens
+        );
+    }
+
+    let mut text = prompt;
+    let mut included = 0;
+
+    for term in glossary {
+        let term = term.replace('\0', "");
+        let candidate = match (text.is_empty(), included) {
+            (true, _) => term,
+            (false, 0) => format!("{} {}", text, term),
+            (false, _) => format!("{}, {}", text, term),
+        };
+        if count_tokens(&candidate)? > max_tokens {
+            break;
+        }
+        text = candidate;
+        included += 1;
+    }
+
+    let dropped_terms = glossary.len() - included;
+    if dropped_terms > 0 {
+        log_warning!(
+            "Glossary truncated to fit the {} token prompt budget: {} of {} terms dropped",
+            max_tokens,
+            dropped_terms,
+            glossary.len()
+        );
+    }
+
+    Ok(InitialPrompt {
+        text,
+        dropped_terms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn count_words(text: &str) -> Result<usize> {
+        Ok(text.split_whitespace().count())
+    }
+
+    fn terms(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|term| term.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_glossary() {
+        let contents = "# product names\nKubeFlow\n\n  Zephyr API  \n#ignored\nquux-db\n";
+        assert_eq!(
+            parse_glossary(contents),
+            vec!["KubeFlow", "Zephyr API", "quux-db"]
+        );
+    }
+
+    #[test]
+    fn test_load_glossary() {
+        let temp_file = NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), "Alpha\nBeta\n").unwrap();
+        let glossary = load_glossary(&temp_file.path().to_string_lossy()).unwrap();
+        assert_eq!(glossary, vec!["Alpha", "Beta"]);
+    }
+
+    #[test]
+    fn test_load_glossary_missing_file() {
+        assert!(load_glossary("/nonexistent/glossary.txt").is_err());
+    }
+
+    #[test]
+    fn test_build_initial_prompt_prompt_only() {
+        let prompt = build_initial_prompt(Some(" Weekly sync. "), &[], 10, count_words).unwrap();
+        assert_eq!(prompt.text, "Weekly sync.");
+        assert_eq!(prompt.dropped_terms, 0);
+    }
+
+    #[test]
+    fn test_build_initial_prompt_with_glossary() {
+        let glossary = terms(&["KubeFlow", "Zephyr"]);
+        let prompt = build_initial_prompt(Some("Glossary:"), &glossary, 10, count_words).unwrap();
+        assert_eq!(prompt.text, "Glossary: KubeFlow, Zephyr");
+    }
+
+    #[test]
+    fn test_build_initial_prompt_glossary_only() {
+        let glossary = terms(&["KubeFlow", "Zephyr"]);
+        let prompt = build_initial_prompt(None, &glossary, 10, count_words).unwrap();
+        assert_eq!(prompt.text, "KubeFlow, Zephyr");
+    }
+
+    #[test]
+    fn test_build_initial_prompt_truncates_glossary() {
+        let glossary = terms(&["one", "two", "three", "four"]);
+        let prompt = build_initial_prompt(Some("Terms:"), &glossary, 3, count_words).unwrap();
+        assert_eq!(prompt.text, "Terms: one, two");
+        assert_eq!(prompt.dropped_terms, 2);
+    }
+
+    #[test]
+    fn test_build_initial_prompt_strips_nul_bytes() {
+        let prompt = build_initial_prompt(Some("a\0b"), &[], 10, count_words).unwrap();
+        assert_eq!(prompt.text, "ab");
+    }
+}
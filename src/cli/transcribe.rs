@@ -1,61 +1,515 @@
-use crate::audio::load_audio;
-use crate::cli::TranscribeArgs;
-use crate::error::Result;
+use crate::audio::{
+    detect_speech, load_audio_channels, load_audio_with_options, LoadOptions, SpeechTimeline,
+    VadOptions, SAMPLE_RATE,
+};
+use crate::cli::detect_language::detect_language;
+use crate::cli::prompt::{build_initial_prompt, load_glossary};
+use crate::cli::{SamplingArgs, TranscribeArgs};
+use crate::error::{Result, WhisperError};
+use crate::log_error;
 use crate::log_info;
 use crate::log_success;
-use crate::model::load_model;
-use std::fs::File;
-use std::io::Write;
+use crate::log_warning;
+use crate::model::{
+    find_english_only_model, load_model_from_path, resolve_model_path, DEFAULT_MODEL,
+};
+use crate::output::transcript::{
+    average_probability, interleave_segments, is_blank_segment, words_from_tokens, Segment, Token,
+    Transcript,
+};
+use crate::output::vtt::CueSettings;
+use crate::output::{write_output, OutputFormat, RenderOptions};
+use std::fs;
+use std::path::PathBuf;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperState};
+
+const DETECTION_WINDOW_SECS: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Task {
+    Transcribe,
+    Translate,
+}
 
 pub fn execute(args: &TranscribeArgs) -> Result<()> {
+    run(args, Task::Transcribe)
+}
+
+pub fn run(args: &TranscribeArgs, task: Task) -> Result<()> {
     log_info!("Loading audio from {}...", args.file_name);
-    let audio_data = load_audio(&args.file_name)?;
+    let load_options = LoadOptions {
+        threads: args.threads,
+        raw: args.raw.raw_format(),
+        channels: args.channels.channel_mix(),
+        resample_quality: args.resample_quality,
+        preprocess: args.preprocess.preprocess_options(),
+    };
+    let tracks = if args.split_channels {
+        load_audio_channels(&args.file_name, &load_options)?
+    } else {
+        vec![load_audio_with_options(&args.file_name, &load_options)?]
+    };
+    let first_track = tracks.first().ok_or(WhisperError::AudioLoadFailed)?;
+
+    let timelines: Vec<Option<SpeechTimeline>> = match args.vad.vad_options() {
+        Some(options) => tracks
+            .iter()
+            .map(|track| Some(speech_timeline(&track.samples, &options)))
+            .collect(),
+        None => vec![None; tracks.len()],
+    };
+    let detection_samples = match &timelines[0] {
+        Some(timeline) if !timeline.is_empty() => &timeline.samples,
+        _ => &first_track.samples,
+    };
 
     log_info!("Loading model...");
-    let context = load_model(args.model.as_deref(), args.language.as_deref())?;
+    let selection = select_model(args, task, detection_samples)?;
+    let context = &selection.context;
 
-    log_info!("Running transcription...");
-    let mut state = context.create_state()?;
-    let mut full_params =
-        whisper_rs::FullParams::new(whisper_rs::SamplingStrategy::Greedy { best_of: 1 });
+    let task_name = match task {
+        Task::Transcribe => "transcription",
+        Task::Translate => "translation",
+    };
+    let initial_prompt = resolve_initial_prompt(args, context)?;
 
-    if let Some(lang) = &args.language {
-        full_params.set_language(Some(lang));
-    } else {
-        full_params.set_language(None);
+    log_info!("Running {}...", task_name);
+    let mut channel_segments = Vec::new();
+    for (index, (track, timeline)) in tracks.iter().zip(&timelines).enumerate() {
+        if args.split_channels {
+            log_info!("Transcribing channel {} of {}...", index + 1, tracks.len());
+        }
+        let mut segments = match timeline {
+            Some(timeline) if timeline.is_empty() => {
+                log_warning!("No speech detected, skipping inference");
+                Vec::new()
+            }
+            Some(timeline) => {
+                let mut segments = transcribe_samples(
+                    args,
+                    task,
+                    &selection,
+                    initial_prompt.as_deref(),
+                    &timeline.samples,
+                )?;
+                for segment in &mut segments {
+                    segment.map_times(|ms| timeline.to_original_ms(ms));
+                }
+                segments
+            }
+            None => transcribe_samples(
+                args,
+                task,
+                &selection,
+                initial_prompt.as_deref(),
+                &track.samples,
+            )?,
+        };
+        if args.split_channels {
+            let label = channel_label(args.channel_labels.as_deref(), index);
+            for segment in &mut segments {
+                segment.speaker = Some(label.clone());
+            }
+        }
+        channel_segments.push(segments);
+    }
+
+    let mut transcript = Transcript {
+        segments: interleave_segments(channel_segments),
+        ..Default::default()
+    };
+    transcript.source_file = Some(args.file_name.clone());
+    transcript.model = Some(args.model.as_deref().unwrap_or(DEFAULT_MODEL).to_string());
+    transcript.model_path = Some(selection.model_path.to_string_lossy().to_string());
+    transcript.language = Some(selection.language.clone());
+    transcript.language_probability = selection.language_probability;
+    transcript.duration_ms = Some(first_track.samples.len() as i64 * 1000 / SAMPLE_RATE as i64);
+
+    let render_options = RenderOptions {
+        cue_settings: CueSettings {
+            line: args.vtt_line.clone(),
+            position: args.vtt_position.clone(),
+            align: args.vtt_align,
+        },
+        words_per_cue: if args.word_timestamps {
+            Some(args.words_per_cue.unwrap_or(1) as usize)
+        } else {
+            None
+        },
+    };
+    let output = args.output_format.render(&transcript, &render_options)?;
+
+    write_output(&output, args.outfile.as_deref())?;
+    if let Some(outfile) = &args.outfile {
+        log_success!("Transcription saved to {}", outfile);
     }
 
-    full_params.set_n_threads(4);
+    log_success!("Transcription complete");
+    Ok(())
+}
+
+fn transcribe_samples(
+    args: &TranscribeArgs,
+    task: Task,
+    selection: &ModelSelection,
+    initial_prompt: Option<&str>,
+    samples: &[f32],
+) -> Result<Vec<Segment>> {
+    let context = &selection.context;
+    let mut state = context.create_state()?;
+    let mut full_params = FullParams::new(sampling_strategy(&args.sampling));
+    apply_sampling(&mut full_params, &args.sampling);
+    if let Some(initial_prompt) = initial_prompt {
+        full_params.set_initial_prompt(initial_prompt);
+    }
+
+    full_params.set_language(Some(&selection.language));
+    full_params.set_translate(task == Task::Translate);
+    full_params.set_n_threads(args.threads as i32);
     full_params.set_print_special(false);
     full_params.set_print_progress(false);
     full_params.set_print_realtime(false);
     full_params.set_print_timestamps(false);
 
-    state.full(full_params, &audio_data.samples[..])?;
+    if needs_token_timestamps(args) {
+        full_params.set_token_timestamps(true);
+    }
+    if args.word_timestamps {
+        full_params.set_split_on_word(true);
+        if let Some(max_len) = args.max_len {
+            full_params.set_max_len(max_len as i32);
+        }
+    }
+
+    state.full(full_params, samples)?;
 
-    let num_segments = state.full_n_segments()?;
+    collect_segments(context, &state, args.word_timestamps)
+}
+
+/// whisper.cpp leaves token times at -1 unless token timestamps are on, and
+/// JSON output includes a time for every token.
+fn needs_token_timestamps(args: &TranscribeArgs) -> bool {
+    args.word_timestamps || args.output_format == OutputFormat::Json
+}
+
+fn speech_timeline(samples: &[f32], options: &VadOptions) -> SpeechTimeline {
+    let timeline = SpeechTimeline::new(samples, detect_speech(samples, options));
+    log_info!(
+        "Voice activity detection kept {:.1}s of {:.1}s",
+        timeline.speech_ms() as f32 / 1000.0,
+        samples.len() as f32 / SAMPLE_RATE as f32
+    );
+    timeline
+}
+
+/// Label for a channel in `--split-channels` mode: the user-supplied name if
+/// there is one, otherwise `CH1`, `CH2`, ...
+fn channel_label(labels: Option<&[String]>, index: usize) -> String {
+    labels
+        .and_then(|labels| labels.get(index))
+        .cloned()
+        .unwrap_or_else(|| format!("CH{}", index + 1))
+}
+
+fn sampling_strategy(sampling: &SamplingArgs) -> SamplingStrategy {
+    match sampling.beam_size {
+        Some(beam_size) => SamplingStrategy::BeamSearch {
+            beam_size: beam_size as i32,
+            // patience is not implemented in whisper.cpp; -1 keeps its default
+            patience: -1.0,
+        },
+        None => SamplingStrategy::Greedy {
+            best_of: sampling.best_of as i32,
+        },
+    }
+}
 
-    let mut transcription = String::new();
+fn apply_sampling(params: &mut FullParams, sampling: &SamplingArgs) {
+    params.set_temperature(sampling.temperature);
+    params.set_temperature_inc(sampling.temperature_increment);
+    params.set_entropy_thold(sampling.entropy_threshold);
+    params.set_logprob_thold(sampling.logprob_threshold);
+    params.set_no_speech_thold(sampling.no_speech_threshold);
+}
+
+fn resolve_initial_prompt(
+    args: &TranscribeArgs,
+    context: &WhisperContext,
+) -> Result<Option<String>> {
+    let prompt = match &args.prompt_file {
+        Some(path) => Some(fs::read_to_string(path)?),
+        None => args.prompt.clone(),
+    };
+    let glossary = match &args.glossary {
+        Some(path) => load_glossary(path)?,
+        None => Vec::new(),
+    };
+
+    if prompt.is_none() && glossary.is_empty() {
+        return Ok(None);
+    }
+
+    // whisper.cpp keeps at most half of the text context for prompt tokens
+    let max_tokens = (context.n_text_ctx() / 2) as usize;
+    let initial_prompt = build_initial_prompt(prompt.as_deref(), &glossary, max_tokens, |text| {
+        Ok(context.tokenize(text, text.len() + 1)?.len())
+    })?;
+
+    Ok(Some(initial_prompt.text).filter(|text| !text.is_empty()))
+}
+
+struct ModelSelection {
+    model_path: PathBuf,
+    context: WhisperContext,
+    language: String,
+    language_probability: Option<f32>,
+}
+
+impl ModelSelection {
+    fn load(
+        args: &TranscribeArgs,
+        model_path: PathBuf,
+        language: String,
+        language_probability: Option<f32>,
+    ) -> Result<Self> {
+        let context = load_model_from_path(&model_path, args.verify_model)?;
+        Ok(ModelSelection {
+            model_path,
+            context,
+            language,
+            language_probability,
+        })
+    }
+}
+
+fn ensure_multilingual(context: &WhisperContext, language: &str, task: Task) -> Result<()> {
+    if context.is_multilingual() || (language == "en" && task == Task::Transcribe) {
+        return Ok(());
+    }
+
+    if task == Task::Translate {
+        log_error!("Translation requires a multilingual model, not an English-only .en model");
+    } else {
+        log_error!(
+            "Language '{}' requires a multilingual model, not an English-only .en model",
+            language
+        );
+    }
+    Err(WhisperError::ModelNotMultilingual)
+}
+
+/// Picks the model and decoding language. Without `--language` the language is
+/// detected on a multilingual model first, so English-only `.en` models are
+/// only used for audio that is actually English.
+fn select_model(args: &TranscribeArgs, task: Task, samples: &[f32]) -> Result<ModelSelection> {
+    let model_name = args.model.as_deref();
+
+    if let Some(language) = &args.language {
+        let multilingual = task == Task::Translate || language != "en";
+        let model_path = resolve_model_path(model_name, Some(language), multilingual)?;
+        let selection = ModelSelection::load(args, model_path, language.clone(), None)?;
+        ensure_multilingual(&selection.context, language, task)?;
+        return Ok(selection);
+    }
+
+    let model_path = resolve_model_path(model_name, None, true)?;
+    let selection = ModelSelection::load(args, model_path, "en".to_string(), None)?;
+
+    if !selection.context.is_multilingual() {
+        ensure_multilingual(&selection.context, "en", task)?;
+        log_warning!(
+            "English-only model selected, skipping language detection and assuming English"
+        );
+        return Ok(selection);
+    }
+
+    let detection_window = &samples[..samples
+        .len()
+        .min(DETECTION_WINDOW_SECS * SAMPLE_RATE as usize)];
+    let detected = detect_language(&selection.context, detection_window, 1, args.threads)?
+        .into_iter()
+        .next()
+        .ok_or(WhisperError::TranscriptionFailed)?;
+    log_info!(
+        "Detected language: {} ({}, {:.0}%)",
+        detected.code,
+        detected.name,
+        detected.probability * 100.0
+    );
+
+    if detected.code == "en" && task == Task::Transcribe {
+        if let Some(english_path) = find_english_only_model(model_name) {
+            log_info!("Switching to English-only model {}", english_path.display());
+            // free the multilingual context first so both models are never
+            // held in memory at once
+            let multilingual_path = selection.model_path;
+            drop(selection.context);
+
+            return match ModelSelection::load(
+                args,
+                english_path,
+                detected.code.clone(),
+                Some(detected.probability),
+            ) {
+                Ok(english) => Ok(english),
+                Err(e) => {
+                    log_warning!(
+                        "Failed to load English-only model ({}), keeping {}",
+                        e,
+                        multilingual_path.display()
+                    );
+                    ModelSelection::load(
+                        args,
+                        multilingual_path,
+                        detected.code,
+                        Some(detected.probability),
+                    )
+                }
+            };
+        }
+    }
+
+    Ok(ModelSelection {
+        language: detected.code,
+        language_probability: Some(detected.probability),
+        ..selection
+    })
+}
+
+fn collect_segments(
+    context: &WhisperContext,
+    state: &WhisperState,
+    word_timestamps: bool,
+) -> Result<Vec<Segment>> {
+    let num_segments = state.full_n_segments()?;
+    let mut segments = Vec::new();
 
     for i in 0..num_segments {
         let segment_text = state.full_get_segment_text(i)?;
         let trimmed = segment_text.trim();
-        if !trimmed.is_empty() && trimmed != "[BLANK_AUDIO]" {
-            transcription.push_str(trimmed);
-            transcription.push(' ');
+        if is_blank_segment(trimmed) {
+            continue;
         }
+
+        let tokens = collect_tokens(context, state, i)?;
+        let words = if word_timestamps {
+            words_from_tokens(&tokens)
+        } else {
+            Vec::new()
+        };
+
+        // whisper reports segment boundaries in 10 ms units
+        segments.push(Segment {
+            start_ms: state.full_get_segment_t0(i)? * 10,
+            end_ms: state.full_get_segment_t1(i)? * 10,
+            text: trimmed.to_string(),
+            speaker: None,
+            avg_probability: average_probability(&tokens),
+            tokens,
+            words,
+        });
     }
 
-    let output = transcription.trim().to_string();
+    Ok(segments)
+}
 
-    if let Some(outfile) = &args.outfile {
-        let mut file = File::create(outfile)?;
-        file.write_all(output.as_bytes())?;
-        log_success!("Transcription saved to {}", outfile);
-    } else {
-        println!("{}", output);
+fn collect_tokens(
+    context: &WhisperContext,
+    state: &WhisperState,
+    segment: i32,
+) -> Result<Vec<Token>> {
+    let num_tokens = state.full_n_tokens(segment)?;
+    let mut tokens = Vec::new();
+
+    for j in 0..num_tokens {
+        let data = state.full_get_token_data(segment, j)?;
+        // control tokens ([_BEG_], [_TT_*], ...) all sit at or above end-of-text
+        if data.id >= context.token_eot() {
+            continue;
+        }
+
+        tokens.push(Token {
+            text: state.full_get_token_text_lossy(segment, j)?,
+            probability: data.p,
+            start_ms: token_ms(data.t0),
+            end_ms: token_ms(data.t1),
+        });
     }
 
-    log_success!("Transcription complete");
-    Ok(())
+    Ok(tokens)
+}
+
+/// Converts a token time in centiseconds to milliseconds. Times whisper.cpp
+/// did not compute (-1) become 0 rather than a negative time.
+fn token_ms(centiseconds: i64) -> i64 {
+    centiseconds.max(0) * 10
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use clap::Parser;
+
+    fn transcribe_args(extra: &[&str]) -> TranscribeArgs {
+        let mut argv = vec!["whisper-rs-cli", "transcribe", "a.wav"];
+        argv.extend(extra);
+        match Cli::parse_from(argv).command {
+            Commands::Transcribe(args) => args,
+            _ => panic!("Expected Transcribe command"),
+        }
+    }
+
+    #[test]
+    fn test_needs_token_timestamps() {
+        assert!(!needs_token_timestamps(&transcribe_args(&[])));
+        assert!(!needs_token_timestamps(&transcribe_args(&["-f", "srt"])));
+        assert!(needs_token_timestamps(&transcribe_args(&["-f", "json"])));
+        assert!(needs_token_timestamps(&transcribe_args(&[
+            "--word-timestamps"
+        ])));
+    }
+
+    #[test]
+    fn test_token_ms_never_negative() {
+        assert_eq!(token_ms(-1), 0);
+        assert_eq!(token_ms(0), 0);
+        assert_eq!(token_ms(152), 1_520);
+    }
+
+    fn sampling_args(beam_size: Option<u8>, best_of: u8) -> SamplingArgs {
+        SamplingArgs {
+            beam_size,
+            best_of,
+            temperature: 0.0,
+            temperature_increment: 0.2,
+            entropy_threshold: 2.4,
+            logprob_threshold: -1.0,
+            no_speech_threshold: 0.6,
+        }
+    }
+
+    #[test]
+    fn test_channel_label() {
+        let labels = vec!["Agent".to_string(), "Customer".to_string()];
+        assert_eq!(channel_label(Some(&labels), 0), "Agent");
+        assert_eq!(channel_label(Some(&labels), 1), "Customer");
+        assert_eq!(channel_label(Some(&labels), 2), "CH3");
+        assert_eq!(channel_label(None, 0), "CH1");
+    }
+
+    #[test]
+    fn test_sampling_strategy_greedy() {
+        let strategy = sampling_strategy(&sampling_args(None, 3));
+        assert!(matches!(strategy, SamplingStrategy::Greedy { best_of: 3 }));
+    }
+
+    #[test]
+    fn test_sampling_strategy_beam_search() {
+        let strategy = sampling_strategy(&sampling_args(Some(5), 3));
+        assert!(matches!(
+            strategy,
+            SamplingStrategy::BeamSearch { beam_size: 5, .. }
+        ));
+    }
 }
@@ -0,0 +1,7 @@
+use crate::cli::transcribe::{run, Task};
+use crate::cli::TranscribeArgs;
+use crate::error::Result;
+
+pub fn execute(args: &TranscribeArgs) -> Result<()> {
+    run(args, Task::Translate)
+}
@@ -10,18 +10,30 @@ pub enum WhisperError {
     AudioConversionFailed,
     #[error("Failed to load audio")]
     AudioLoadFailed,
+    #[error("Unsupported WAV encoding: {0}")]
+    UnsupportedWavEncoding(String),
     #[error("Model not found")]
     ModelNotFound,
     #[error("Model download failed")]
     ModelDownloadFailed,
     #[error("Failed to load model")]
     ModelLoadFailed,
+    #[error("Model checksum mismatch for {file}: expected {expected}, got {actual}")]
+    ModelChecksumMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Model is English-only and does not support this task")]
+    ModelNotMultilingual,
     #[error("Transcription failed")]
     TranscriptionFailed,
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
     #[error("HTTP error: {0}")]
     HttpError(#[from] reqwest::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
     #[error("Whisper error: {0}")]
     WhisperError(#[from] whisper_rs::WhisperError),
     #[error(transparent)]
@@ -49,6 +61,10 @@ mod tests {
             WhisperError::AudioLoadFailed.to_string(),
             "Failed to load audio"
         );
+        assert_eq!(
+            WhisperError::UnsupportedWavEncoding("64-bit float".to_string()).to_string(),
+            "Unsupported WAV encoding: 64-bit float"
+        );
         assert_eq!(WhisperError::ModelNotFound.to_string(), "Model not found");
         assert_eq!(
             WhisperError::ModelDownloadFailed.to_string(),
@@ -58,6 +74,19 @@ mod tests {
             WhisperError::ModelLoadFailed.to_string(),
             "Failed to load model"
         );
+        assert_eq!(
+            WhisperError::ModelChecksumMismatch {
+                file: "ggml-base.bin".to_string(),
+                expected: "aa".to_string(),
+                actual: "bb".to_string(),
+            }
+            .to_string(),
+            "Model checksum mismatch for ggml-base.bin: expected aa, got bb"
+        );
+        assert_eq!(
+            WhisperError::ModelNotMultilingual.to_string(),
+            "Model is English-only and does not support this task"
+        );
         assert_eq!(
             WhisperError::TranscriptionFailed.to_string(),
             "Transcription failed"
@@ -5,4 +5,5 @@ pub mod model;
 pub mod output;
 
 pub use error::{Result, WhisperError};
+pub use model::downloader::set_offline;
 pub use output::logger::{init_whisper_logging, set_silent};
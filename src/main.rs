@@ -2,6 +2,7 @@ use clap::Parser;
 use whisper_rs_cli::cli::Commands;
 use whisper_rs_cli::error::Result;
 use whisper_rs_cli::init_whisper_logging;
+use whisper_rs_cli::set_offline;
 use whisper_rs_cli::set_silent;
 
 fn main() -> Result<()> {
@@ -10,8 +11,12 @@ fn main() -> Result<()> {
     let cli = whisper_rs_cli::cli::Cli::parse();
 
     set_silent(!cli.debug);
+    set_offline(cli.offline);
 
     match cli.command {
         Commands::Transcribe(args) => whisper_rs_cli::cli::transcribe::execute(&args),
+        Commands::Translate(args) => whisper_rs_cli::cli::translate::execute(&args),
+        Commands::DetectLanguage(args) => whisper_rs_cli::cli::detect_language::execute(&args),
+        Commands::Models(args) => whisper_rs_cli::cli::models::execute(&args),
     }
 }
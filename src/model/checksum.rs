@@ -0,0 +1,254 @@
+use crate::error::{Result, WhisperError};
+use crate::model::loader::models_dir_path;
+use crate::{log_info, log_warning};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+
+/// User checksums in `sha256sum` format, read from the models directory.
+/// Entries here take precedence over the built-in table.
+pub const MANIFEST_FILE: &str = "checksums.sha256";
+
+/// SHA-256 of the official ggml models on Hugging Face.
+const KNOWN_CHECKSUMS: &[(&str, &str)] = &[
+    (
+        "ggml-tiny.bin",
+        "be07e048e1e599ad46341c8d2a135645097a538221678b7acdd1b1919c6e1b21",
+    ),
+    (
+        "ggml-tiny.en.bin",
+        "921e4cf8686fdd993dcd081a5da5b6c365bfde1162e72b08d75ac75289920b1f",
+    ),
+    (
+        "ggml-base.bin",
+        "60ed5bc3dd14eea856493d334349b405782ddcaf0028d4b5df4088345fba2efe",
+    ),
+    (
+        "ggml-base.en.bin",
+        "a03779c86df3323075f5e796cb2ce5029f00ec8869eee3fdfb897afe36c6d002",
+    ),
+    (
+        "ggml-small.bin",
+        "1be3a9b2063867b937e64e2ec7483364a79917e157fa98c5d94b5c1fffea987b",
+    ),
+    (
+        "ggml-small.en.bin",
+        "c6138d6d58ecc8322097e0f987c32f1be8bb0a18532a3f88f734d1bbf9c41e5d",
+    ),
+    (
+        "ggml-medium.bin",
+        "6c14d5adee5f86394037b4e4e8b59f1673b6cee10e3cf0b11bbdbee79c156208",
+    ),
+    (
+        "ggml-medium.en.bin",
+        "cc37e93478338ec7700281a7ac30a10128929eb8f427dda2e865faa8f6da4356",
+    ),
+    (
+        "ggml-large-v1.bin",
+        "7d99f41a10525d0206bddadd86760181fa920438b6b33237e3118ff6c83bb53d",
+    ),
+    (
+        "ggml-large-v2.bin",
+        "9a423fe4d40c82774b6af34115b8b935f34152246eb19e80e376071d3f999487",
+    ),
+    (
+        "ggml-large-v3.bin",
+        "64d182b440b98d5203c4f9bd541544d84c605196c4f7b845dfa11fb23594d1e2",
+    ),
+    (
+        "ggml-large-v3-turbo.bin",
+        "1fc70f774d38eb169993ac391eea357ef47c88757ef72ee5943879b7e8e2bc69",
+    ),
+];
+
+/// Names of the official models that can be downloaded, e.g. `base.en`.
+pub fn known_models() -> Vec<&'static str> {
+    KNOWN_CHECKSUMS
+        .iter()
+        .filter_map(|(file_name, _)| file_name.strip_prefix("ggml-")?.strip_suffix(".bin"))
+        .collect()
+}
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Parses `sha256sum` output: `<hex digest>  <file name>` per line, with an
+/// optional `*` before binary file names. Blank lines and `#` comments are
+/// skipped, as are lines that are not a SHA-256 digest.
+fn parse_manifest(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (digest, file_name) = line.split_once(char::is_whitespace)?;
+            let file_name = file_name.trim_start().trim_start_matches('*');
+            let file_name = Path::new(file_name).file_name()?.to_str()?;
+            let is_sha256 = digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit());
+            is_sha256.then(|| (file_name.to_string(), digest.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+fn lookup_checksum(manifest: &[(String, String)], file_name: &str) -> Option<String> {
+    manifest
+        .iter()
+        .find(|(name, _)| name == file_name)
+        .map(|(_, digest)| digest.clone())
+        .or_else(|| {
+            KNOWN_CHECKSUMS
+                .iter()
+                .find(|(name, _)| *name == file_name)
+                .map(|(_, digest)| digest.to_string())
+        })
+}
+
+fn read_manifest() -> Result<Vec<(String, String)>> {
+    let manifest_path = models_dir_path()?.join(MANIFEST_FILE);
+    match fs::read_to_string(&manifest_path) {
+        Ok(contents) => Ok(parse_manifest(&contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The expected SHA-256 for a model file name, from the user manifest or the
+/// built-in table.
+pub fn expected_checksum(file_name: &str) -> Result<Option<String>> {
+    Ok(lookup_checksum(&read_manifest()?, file_name))
+}
+
+/// Fails with `ModelChecksumMismatch` unless `path` hashes to `expected`.
+pub fn check_file(path: &Path, expected: &str) -> Result<()> {
+    log_info!("Verifying SHA-256 of {}...", path.display());
+    let actual = sha256_file(path)?;
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(WhisperError::ModelChecksumMismatch {
+            file: path.display().to_string(),
+            expected: expected.to_ascii_lowercase(),
+            actual,
+        })
+    }
+}
+
+/// Verifies an installed model against its known checksum. Models without
+/// one, such as fine-tuned models missing from the manifest, are skipped
+/// with a warning.
+pub fn verify_model(model_path: &Path) -> Result<()> {
+    let file_name = model_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match expected_checksum(&file_name)? {
+        Some(expected) => check_file(model_path, &expected),
+        None => {
+            log_warning!(
+                "No known checksum for {}, add it to {} to verify it",
+                file_name,
+                MANIFEST_FILE
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SHA-256 of "abc"
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn test_sha256_file() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), b"abc").unwrap();
+        assert_eq!(sha256_file(temp_file.path()).unwrap(), ABC_SHA256);
+    }
+
+    #[test]
+    fn test_check_file() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), b"abc").unwrap();
+
+        assert!(check_file(temp_file.path(), &ABC_SHA256.to_ascii_uppercase()).is_ok());
+
+        let result = check_file(temp_file.path(), &"0".repeat(64));
+        match result {
+            Err(WhisperError::ModelChecksumMismatch { actual, .. }) => {
+                assert_eq!(actual, ABC_SHA256)
+            }
+            other => panic!("Expected checksum mismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = format!(
+            "# fine-tuned models\n\n{}  ggml-custom.bin\n{} *models/ggml-other.bin\nnot-a-digest  ggml-bad.bin\n",
+            ABC_SHA256,
+            ABC_SHA256.to_ascii_uppercase()
+        );
+        assert_eq!(
+            parse_manifest(&manifest),
+            vec![
+                ("ggml-custom.bin".to_string(), ABC_SHA256.to_string()),
+                ("ggml-other.bin".to_string(), ABC_SHA256.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lookup_checksum_prefers_manifest() {
+        let manifest = vec![("ggml-base.bin".to_string(), ABC_SHA256.to_string())];
+        assert_eq!(
+            lookup_checksum(&manifest, "ggml-base.bin").as_deref(),
+            Some(ABC_SHA256)
+        );
+        assert_eq!(
+            lookup_checksum(&[], "ggml-base.bin").as_deref(),
+            Some("60ed5bc3dd14eea856493d334349b405782ddcaf0028d4b5df4088345fba2efe")
+        );
+        assert_eq!(lookup_checksum(&manifest, "ggml-custom.bin"), None);
+    }
+
+    #[test]
+    fn test_known_models() {
+        let models = known_models();
+        assert_eq!(models.len(), KNOWN_CHECKSUMS.len());
+        assert!(models.contains(&"base.en"));
+        assert!(models.contains(&"large-v3-turbo"));
+    }
+
+    #[test]
+    fn test_known_checksums_are_sha256() {
+        for (file_name, digest) in KNOWN_CHECKSUMS {
+            assert!(file_name.starts_with("ggml-") && file_name.ends_with(".bin"));
+            assert_eq!(digest.len(), 64);
+            assert!(digest
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+        }
+    }
+}
@@ -1,74 +1,468 @@
 use crate::error::{Result, WhisperError};
-use crate::log_info;
+use crate::model::checksum::{check_file, expected_checksum};
 use crate::output::logger::is_silent;
+use crate::{log_error, log_info, log_warning};
 use indicatif::{ProgressBar, ProgressStyle};
-use std::fs::File;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+/// Overrides `MODEL_BASE_URL`, e.g. with an internal mirror or a `file://`
+/// directory.
+pub const BASE_URL_ENV: &str = "WHISPER_MODEL_BASE_URL";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Forbids network downloads. Missing models then fail with `ModelNotFound`;
+/// `file://` mirrors keep working.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::SeqCst);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::SeqCst)
+}
+
+fn is_local_url(url: &str) -> bool {
+    url.starts_with("file:")
+}
+
+/// Fails with `ModelNotFound` when fetching the model would need the
+/// network but offline mode is on.
+pub fn ensure_download_allowed(model_name: &str) -> Result<()> {
+    check_download_allowed(model_name, &generate_url(model_name), is_offline())
+}
+
+fn check_download_allowed(model_name: &str, url: &str, offline: bool) -> Result<()> {
+    if offline && !is_local_url(url) {
+        log_error!(
+            "Model '{}' is not installed and --offline prevents downloading it",
+            model_name
+        );
+        return Err(WhisperError::ModelNotFound);
+    }
+    Ok(())
+}
+
+/// Where a download is kept until it completes, e.g. `ggml-base.bin.part`.
+/// Model lookups only match the final name, so a truncated file is never
+/// picked up as a model.
+pub fn partial_path(output_path: &Path) -> PathBuf {
+    let mut file_name = output_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".part");
+    output_path.with_file_name(file_name)
+}
 
 pub fn download_model(model_name: &str, output_path: &Path) -> Result<()> {
-    let url = format!("{}/ggml-{}.bin", MODEL_BASE_URL, model_name);
+    ensure_download_allowed(model_name)?;
+    let url = generate_url(model_name);
 
     log_info!("Downloading model from {}...", url);
 
+    let file_name = output_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let expected = expected_checksum(&file_name)?;
+    if expected.is_none() {
+        log_warning!("No known checksum for {}, skipping verification", file_name);
+    }
+
+    download_file(&url, output_path, expected.as_deref())
+}
+
+/// Downloads into the `.part` file next to `output_path` and renames it into
+/// place only once it is complete and, when `expected` is given, its SHA-256
+/// matches.
+fn download_file(url: &str, output_path: &Path, expected: Option<&str>) -> Result<()> {
+    let part_path = partial_path(output_path);
+
+    if is_local_url(url) {
+        copy_local(url, &part_path)?;
+    } else {
+        fetch_http(url, &part_path)?;
+    }
+
+    if let Some(expected) = expected {
+        if let Err(e) = check_file(&part_path, expected) {
+            // resuming a corrupt file cannot fix it, so start over next time
+            fs::remove_file(&part_path)?;
+            log_error!("Downloaded model is corrupt, run again to download it from scratch");
+            return Err(e);
+        }
+    }
+
+    fs::rename(&part_path, output_path)?;
+    log_info!("Download complete");
+
+    Ok(())
+}
+
+/// Copies a model from a `file://` mirror.
+fn copy_local(url: &str, part_path: &Path) -> Result<()> {
+    let source_path = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.to_file_path().ok())
+        .ok_or_else(|| WhisperError::Other(anyhow::anyhow!("Invalid file URL {}", url)))?;
+
+    let source = File::open(&source_path)?;
+    let total_size = source.metadata()?.len();
+    let mut file = File::create(part_path)?;
+    copy_with_progress(source, &mut file, 0, Some(total_size))?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// Fetches over HTTP(S), resuming an existing `.part` file with a Range
+/// request. Proxies are taken from the usual `HTTPS_PROXY`, `HTTP_PROXY`,
+/// `ALL_PROXY` and `NO_PROXY` environment variables.
+fn fetch_http(url: &str, part_path: &Path) -> Result<()> {
     let client = reqwest::blocking::Client::builder()
         .timeout(Duration::from_secs(600))
         .build()?;
 
-    let response = client.get(&url).send()?;
-    let total_size = response.content_length().unwrap_or(0);
+    let mut offset = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+    let mut response = if offset > 0 {
+        log_info!("Resuming download at {} bytes...", offset);
+        client
+            .get(url)
+            .header(RANGE, format!("bytes={}-", offset))
+            .send()?
+    } else {
+        client.get(url).send()?
+    };
 
-    let mut file = File::create(output_path)?;
+    if offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        // the process was stopped after the last byte arrived but before the
+        // rename, so the partial file is already complete
+        if unsatisfied_range_total(&response) == Some(offset) {
+            log_info!("Partial download is already complete");
+            return Ok(());
+        }
+        log_warning!("Partial download does not match the remote file, restarting...");
+        offset = 0;
+        response = client.get(url).send()?;
+    }
 
-    if !is_silent() && total_size > 0 {
-        let pb = ProgressBar::new(total_size);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})")
-                .unwrap()
-                .progress_chars("#>-"),
-        );
+    let response = response.error_for_status()?;
+    let resumed = offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+    if offset > 0 && !resumed {
+        log_warning!("Server does not support resuming, restarting download...");
+        offset = 0;
+    }
 
-        let mut downloaded = 0u64;
-        let mut source = response;
-        let mut buffer = [0u8; 8192];
+    let mut file = if resumed {
+        OpenOptions::new().append(true).open(part_path)?
+    } else {
+        File::create(part_path)?
+    };
+    let total_size = response.content_length().map(|len| offset + len);
 
-        loop {
-            let bytes_read = source
-                .read(&mut buffer)
-                .map_err(|_| WhisperError::ModelDownloadFailed)?;
-            if bytes_read == 0 {
-                break;
-            }
+    let downloaded = match copy_with_progress(response, &mut file, offset, total_size) {
+        Ok(downloaded) => downloaded,
+        Err(e) => {
+            log_warning!(
+                "Download interrupted, the partial file {} will be resumed on the next attempt",
+                part_path.display()
+            );
+            return Err(e);
+        }
+    };
+    file.sync_all()?;
+    drop(file);
+
+    if let Some(total_size) = total_size {
+        if downloaded != total_size {
+            log_warning!(
+                "Download ended after {} of {} bytes, the partial file {} will be resumed on the next attempt",
+                downloaded,
+                total_size,
+                part_path.display()
+            );
+            return Err(WhisperError::ModelDownloadFailed);
+        }
+    }
+
+    Ok(())
+}
+
+/// The remote file size from a 416 answer's `Content-Range: bytes */<total>`.
+fn unsatisfied_range_total(response: &reqwest::blocking::Response) -> Option<u64> {
+    parse_unsatisfied_range(response.headers().get(CONTENT_RANGE)?.to_str().ok()?)
+}
+
+fn parse_unsatisfied_range(content_range: &str) -> Option<u64> {
+    content_range
+        .trim()
+        .strip_prefix("bytes */")?
+        .trim()
+        .parse()
+        .ok()
+}
 
-            file.write_all(&buffer[..bytes_read])?;
-            downloaded += bytes_read as u64;
+/// Appends the response body to `file` and returns the file's new length.
+fn copy_with_progress(
+    mut source: impl Read,
+    file: &mut File,
+    offset: u64,
+    total_size: Option<u64>,
+) -> Result<u64> {
+    let pb = match total_size {
+        Some(total_size) if !is_silent() && total_size > 0 => {
+            let pb = ProgressBar::new(total_size);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template("{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+                    .unwrap()
+                    .progress_chars("#>-"),
+            );
+            pb.set_position(offset);
+            Some(pb)
+        }
+        _ => None,
+    };
+
+    let mut downloaded = offset;
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        let bytes_read = source
+            .read(&mut buffer)
+            .map_err(|_| WhisperError::ModelDownloadFailed)?;
+        if bytes_read == 0 {
+            break;
+        }
+
+        file.write_all(&buffer[..bytes_read])?;
+        downloaded += bytes_read as u64;
+        if let Some(pb) = &pb {
             pb.set_position(downloaded);
         }
+    }
 
-        pb.finish_with_message("Download complete");
-    } else {
-        let mut source = response;
-        std::io::copy(&mut source, &mut file)?;
-        log_info!("Download complete");
+    if let Some(pb) = pb {
+        pb.finish();
     }
 
-    Ok(())
+    Ok(downloaded)
+}
+
+fn base_url(configured: Option<String>) -> String {
+    configured
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| MODEL_BASE_URL.to_string())
+}
+
+fn url_for(base_url: &str, model_name: &str) -> String {
+    format!("{}/ggml-{}.bin", base_url, model_name)
 }
 
 pub fn generate_url(model_name: &str) -> String {
-    format!("{}/ggml-{}.bin", MODEL_BASE_URL, model_name)
+    generate_url_with(std::env::var(BASE_URL_ENV).ok(), model_name)
+}
+
+fn generate_url_with(configured_base: Option<String>, model_name: &str) -> String {
+    url_for(&base_url(configured_base), model_name)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::BufRead;
+    use std::net::TcpListener;
+
+    const BODY: &[u8] = b"ggml model weights, 0123456789 abcdefghijklmnopqrstuvwxyz";
+
+    /// Serves `BODY` for `requests` connections. The first response is cut
+    /// off after `cut_at` bytes when set; Range requests get a 206 unless
+    /// `honor_range` is false.
+    fn serve(requests: usize, cut_at: Option<usize>, honor_range: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ggml-test.bin", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            for (index, stream) in listener.incoming().take(requests).enumerate() {
+                let mut stream = stream.unwrap();
+                let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+                let mut start = None;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(range) = line.to_lowercase().strip_prefix("range: bytes=") {
+                        start = range.trim().trim_end_matches('-').parse::<usize>().ok();
+                    }
+                }
+
+                let (status, body) = match start.filter(|_| honor_range) {
+                    Some(start) if start >= BODY.len() => (
+                        format!(
+                            "416 Range Not Satisfiable\r\nContent-Range: bytes */{}",
+                            BODY.len()
+                        ),
+                        &BODY[..0],
+                    ),
+                    Some(start) => (
+                        format!(
+                            "206 Partial Content\r\nContent-Range: bytes {}-{}/{}",
+                            start,
+                            BODY.len() - 1,
+                            BODY.len()
+                        ),
+                        &BODY[start..],
+                    ),
+                    None => ("200 OK".to_string(), BODY),
+                };
+                let sent = match cut_at {
+                    Some(cut_at) if index == 0 => &body[..cut_at],
+                    _ => body,
+                };
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                let _ = stream.write_all(sent);
+            }
+        });
+
+        url
+    }
+
+    #[test]
+    fn test_partial_path() {
+        assert_eq!(
+            partial_path(Path::new("/models/ggml-base.bin")),
+            PathBuf::from("/models/ggml-base.bin.part")
+        );
+    }
+
+    #[test]
+    fn test_download_file_renames_on_completion() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("ggml-test.bin");
+
+        download_file(&serve(1, None, true), &output_path, None).unwrap();
+
+        assert_eq!(fs::read(&output_path).unwrap(), BODY);
+        assert!(!partial_path(&output_path).exists());
+    }
+
+    #[test]
+    fn test_download_file_resumes_interrupted_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("ggml-test.bin");
+        let url = serve(2, Some(20), true);
+
+        assert!(download_file(&url, &output_path, None).is_err());
+        assert!(!output_path.exists());
+        assert_eq!(fs::read(partial_path(&output_path)).unwrap(), &BODY[..20]);
+
+        download_file(&url, &output_path, None).unwrap();
+        assert_eq!(fs::read(&output_path).unwrap(), BODY);
+        assert!(!partial_path(&output_path).exists());
+    }
+
+    #[test]
+    fn test_download_file_verifies_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("ggml-test.bin");
+        let url = serve(2, None, true);
+
+        let result = download_file(&url, &output_path, Some(&"0".repeat(64)));
+        assert!(matches!(
+            result,
+            Err(WhisperError::ModelChecksumMismatch { .. })
+        ));
+        assert!(!output_path.exists());
+        assert!(!partial_path(&output_path).exists());
+
+        let body_path = dir.path().join("body");
+        fs::write(&body_path, BODY).unwrap();
+        let expected = crate::model::checksum::sha256_file(&body_path).unwrap();
+        download_file(&url, &output_path, Some(&expected)).unwrap();
+        assert_eq!(fs::read(&output_path).unwrap(), BODY);
+    }
+
+    #[test]
+    fn test_download_file_from_file_url() {
+        let mirror = tempfile::tempdir().unwrap();
+        let source_path = mirror.path().join("ggml-test.bin");
+        fs::write(&source_path, BODY).unwrap();
+        let url = reqwest::Url::from_file_path(&source_path).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("ggml-test.bin");
+        download_file(url.as_str(), &output_path, None).unwrap();
+
+        assert_eq!(fs::read(&output_path).unwrap(), BODY);
+        assert!(!partial_path(&output_path).exists());
+
+        let missing = reqwest::Url::from_file_path(mirror.path().join("ggml-missing.bin")).unwrap();
+        assert!(download_file(missing.as_str(), &output_path, None).is_err());
+    }
+
+    #[test]
+    fn test_download_file_restarts_without_range_support() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("ggml-test.bin");
+        fs::write(partial_path(&output_path), &BODY[..10]).unwrap();
+
+        download_file(&serve(1, None, false), &output_path, None).unwrap();
+
+        assert_eq!(fs::read(&output_path).unwrap(), BODY);
+    }
+
+    #[test]
+    fn test_download_file_completes_finished_partial() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("ggml-test.bin");
+        fs::write(partial_path(&output_path), BODY).unwrap();
+
+        // a single connection: restarting the download would fail
+        download_file(&serve(1, None, true), &output_path, None).unwrap();
+
+        assert_eq!(fs::read(&output_path).unwrap(), BODY);
+        assert!(!partial_path(&output_path).exists());
+    }
+
+    #[test]
+    fn test_parse_unsatisfied_range() {
+        assert_eq!(parse_unsatisfied_range("bytes */1234"), Some(1234));
+        assert_eq!(parse_unsatisfied_range("bytes 0-9/1234"), None);
+        assert_eq!(parse_unsatisfied_range("bytes */*"), None);
+    }
+
+    #[test]
+    fn test_download_file_restarts_oversized_partial() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_path = dir.path().join("ggml-test.bin");
+        let mut stale = BODY.to_vec();
+        stale.extend_from_slice(b"trailing garbage");
+        fs::write(partial_path(&output_path), stale).unwrap();
+
+        download_file(&serve(2, None, true), &output_path, None).unwrap();
+
+        assert_eq!(fs::read(&output_path).unwrap(), BODY);
+    }
 
     #[test]
     fn test_generate_url() {
+        // a configured mirror replaces the default base
+        if std::env::var_os(BASE_URL_ENV).is_some() {
+            return;
+        }
+
         let url = generate_url("base");
         assert_eq!(
             url,
@@ -94,12 +488,60 @@ mod tests {
             "tiny", "base", "small", "medium", "large", "large-v1", "large-v2", "large-v3",
         ];
         for model in models {
-            let url = generate_url(model);
+            let url = generate_url_with(None, model);
             assert!(url.contains(model));
             assert!(url.starts_with("https://huggingface.co"));
         }
     }
 
+    #[test]
+    fn test_generate_url_with_mirror() {
+        assert_eq!(
+            generate_url_with(
+                Some("http://artifacts.internal/whisper/".to_string()),
+                "base"
+            ),
+            "http://artifacts.internal/whisper/ggml-base.bin"
+        );
+        assert_eq!(
+            generate_url_with(Some("file:///mnt/models".to_string()), "large-v3"),
+            "file:///mnt/models/ggml-large-v3.bin"
+        );
+    }
+
+    #[test]
+    fn test_base_url() {
+        assert_eq!(base_url(None), MODEL_BASE_URL);
+        assert_eq!(base_url(Some("  ".to_string())), MODEL_BASE_URL);
+        assert_eq!(
+            base_url(Some("http://artifacts.internal/whisper/".to_string())),
+            "http://artifacts.internal/whisper"
+        );
+        assert_eq!(
+            base_url(Some("file:///mnt/models".to_string())),
+            "file:///mnt/models"
+        );
+    }
+
+    #[test]
+    fn test_check_download_allowed_offline() {
+        let url = url_for(MODEL_BASE_URL, "base");
+        assert!(matches!(
+            check_download_allowed("base", &url, true),
+            Err(WhisperError::ModelNotFound)
+        ));
+        assert!(check_download_allowed("base", &url, false).is_ok());
+
+        let mirror = url_for("file:///mnt/models", "base");
+        assert!(check_download_allowed("base", &mirror, true).is_ok());
+    }
+
+    #[test]
+    fn test_is_local_url() {
+        assert!(is_local_url("file:///mnt/models/ggml-base.bin"));
+        assert!(!is_local_url(&url_for(MODEL_BASE_URL, "base")));
+    }
+
     #[test]
     fn test_model_base_url() {
         assert_eq!(
@@ -0,0 +1,201 @@
+use crate::error::{Result, WhisperError};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+// "ggml" read as a little-endian u32
+const GGML_FILE_MAGIC: u32 = 0x6767_6d6c;
+// ggml stores the quantization format version in the thousands of `ftype`.
+const QNT_VERSION_FACTOR: i32 = 1000;
+// Multilingual vocabularies have 51865 tokens (51866 for large-v3), English
+// ones 51864.
+const MULTILINGUAL_VOCAB: i32 = 51865;
+const HEADER_LEN: usize = 4 + 11 * 4;
+
+/// Hyperparameters from the header of a whisper.cpp ggml model file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelHeader {
+    pub n_vocab: i32,
+    pub n_audio_ctx: i32,
+    pub n_audio_state: i32,
+    pub n_audio_head: i32,
+    pub n_audio_layer: i32,
+    pub n_text_ctx: i32,
+    pub n_text_state: i32,
+    pub n_text_head: i32,
+    pub n_text_layer: i32,
+    pub n_mels: i32,
+    pub ftype: i32,
+}
+
+impl ModelHeader {
+    pub fn is_multilingual(&self) -> bool {
+        self.n_vocab >= MULTILINGUAL_VOCAB
+    }
+
+    /// Model size, inferred from the encoder depth the way whisper.cpp does.
+    pub fn model_type(&self) -> &'static str {
+        match self.n_audio_layer {
+            4 => "tiny",
+            6 => "base",
+            12 => "small",
+            24 => "medium",
+            32 => "large",
+            _ => "unknown",
+        }
+    }
+
+    /// Weight type, named after ggml's `ggml_ftype`.
+    pub fn quantization(&self) -> &'static str {
+        match self.ftype % QNT_VERSION_FACTOR {
+            0 => "f32",
+            1 => "f16",
+            2 => "q4_0",
+            3 => "q4_1",
+            4 => "q4_1 (some f16)",
+            7 => "q8_0",
+            8 => "q5_0",
+            9 => "q5_1",
+            10 => "q2_k",
+            11 => "q3_k",
+            12 => "q4_k",
+            13 => "q5_k",
+            14 => "q6_k",
+            24 => "bf16",
+            _ => "unknown",
+        }
+    }
+
+    pub fn quantization_version(&self) -> i32 {
+        self.ftype / QNT_VERSION_FACTOR
+    }
+}
+
+fn parse_header(data: &[u8]) -> Option<ModelHeader> {
+    let mut words = data
+        .get(..HEADER_LEN)?
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]));
+    if words.next()? != GGML_FILE_MAGIC {
+        return None;
+    }
+    let mut next = || words.next().map(|word| word as i32);
+
+    Some(ModelHeader {
+        n_vocab: next()?,
+        n_audio_ctx: next()?,
+        n_audio_state: next()?,
+        n_audio_head: next()?,
+        n_audio_layer: next()?,
+        n_text_ctx: next()?,
+        n_text_state: next()?,
+        n_text_head: next()?,
+        n_text_layer: next()?,
+        n_mels: next()?,
+        ftype: next()?,
+    })
+}
+
+/// Reads the header without loading the weights.
+pub fn read_model_header(path: &Path) -> Result<ModelHeader> {
+    let mut data = Vec::with_capacity(HEADER_LEN);
+    File::open(path)?
+        .take(HEADER_LEN as u64)
+        .read_to_end(&mut data)?;
+
+    parse_header(&data).ok_or_else(|| {
+        WhisperError::Other(anyhow::anyhow!(
+            "{} is not a whisper.cpp ggml model",
+            path.display()
+        ))
+    })
+}
+
+#[cfg(test)]
+pub(crate) fn encode_header(header: &ModelHeader) -> Vec<u8> {
+    [
+        GGML_FILE_MAGIC as i32,
+        header.n_vocab,
+        header.n_audio_ctx,
+        header.n_audio_state,
+        header.n_audio_head,
+        header.n_audio_layer,
+        header.n_text_ctx,
+        header.n_text_state,
+        header.n_text_head,
+        header.n_text_layer,
+        header.n_mels,
+        header.ftype,
+    ]
+    .iter()
+    .flat_map(|value| value.to_le_bytes())
+    .collect()
+}
+
+#[cfg(test)]
+pub(crate) fn base_header() -> ModelHeader {
+    ModelHeader {
+        n_vocab: 51865,
+        n_audio_ctx: 1500,
+        n_audio_state: 512,
+        n_audio_head: 8,
+        n_audio_layer: 6,
+        n_text_ctx: 448,
+        n_text_state: 512,
+        n_text_head: 8,
+        n_text_layer: 6,
+        n_mels: 80,
+        ftype: 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_model_header() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let mut data = encode_header(&base_header());
+        data.extend_from_slice(&[0u8; 64]);
+        std::fs::write(temp_file.path(), data).unwrap();
+
+        let header = read_model_header(temp_file.path()).unwrap();
+        assert_eq!(header, base_header());
+        assert_eq!(header.model_type(), "base");
+        assert!(header.is_multilingual());
+        assert_eq!(header.quantization(), "f16");
+    }
+
+    #[test]
+    fn test_read_model_header_rejects_other_files() {
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), b"RIFF\x24\x00\x00\x00WAVEfmt ").unwrap();
+        assert!(read_model_header(temp_file.path()).is_err());
+
+        let mut truncated = encode_header(&base_header());
+        truncated.truncate(20);
+        assert_eq!(parse_header(&truncated), None);
+    }
+
+    #[test]
+    fn test_header_english_only() {
+        let header = ModelHeader {
+            n_vocab: 51864,
+            n_audio_layer: 4,
+            ..base_header()
+        };
+        assert!(!header.is_multilingual());
+        assert_eq!(header.model_type(), "tiny");
+    }
+
+    #[test]
+    fn test_header_quantization() {
+        let header = ModelHeader {
+            ftype: 2008,
+            ..base_header()
+        };
+        assert_eq!(header.quantization(), "q5_0");
+        assert_eq!(header.quantization_version(), 2);
+    }
+}
@@ -1,15 +1,35 @@
 use crate::error::{Result, WhisperError};
+use crate::log_error;
 use crate::log_info;
 use crate::log_warning;
-use crate::model::downloader::download_model;
+use crate::model::checksum::verify_model;
+use crate::model::downloader::{download_model, ensure_download_allowed};
+use std::env;
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use whisper_rs::WhisperContext;
 
 pub const DEFAULT_MODEL: &str = "base";
+/// Directories searched before the defaults, separated like `PATH`.
+pub const MODEL_PATH_ENV: &str = "WHISPER_MODEL_PATH";
+/// Replaces `~/.local/share/whisper` as the directory models are downloaded
+/// to, and is searched before the defaults.
+pub const MODELS_DIR_ENV: &str = "WHISPER_MODELS_DIR";
+
+fn env_dir(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
 
-fn get_model_search_dirs() -> Vec<PathBuf> {
-    let mut dirs = Vec::new();
+fn search_dirs(model_path: Option<OsString>, models_dir: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = model_path
+        .iter()
+        .flat_map(env::split_paths)
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .collect();
+    dirs.extend(models_dir);
 
     if let Some(home) = dirs::home_dir() {
         dirs.push(home.join(".cache/whispercpp"));
@@ -22,10 +42,26 @@ fn get_model_search_dirs() -> Vec<PathBuf> {
     dirs
 }
 
+pub fn get_model_search_dirs() -> Vec<PathBuf> {
+    search_dirs(env::var_os(MODEL_PATH_ENV), env_dir(MODELS_DIR_ENV))
+}
+
+fn models_dir(configured: Option<PathBuf>) -> Result<PathBuf> {
+    match configured {
+        Some(models_dir) => Ok(models_dir),
+        None => Ok(dirs::home_dir()
+            .ok_or_else(|| WhisperError::Other(anyhow::anyhow!("Home directory not found")))?
+            .join(".local/share/whisper")),
+    }
+}
+
+/// The models directory, without creating it if it does not exist yet.
+pub(crate) fn models_dir_path() -> Result<PathBuf> {
+    models_dir(env_dir(MODELS_DIR_ENV))
+}
+
 pub fn get_models_dir() -> Result<PathBuf> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| WhisperError::Other(anyhow::anyhow!("Home directory not found")))?;
-    let models_dir = home.join(".local/share/whisper");
+    let models_dir = models_dir_path()?;
 
     if !models_dir.exists() {
         fs::create_dir_all(&models_dir)?;
@@ -34,63 +70,178 @@ pub fn get_models_dir() -> Result<PathBuf> {
     Ok(models_dir)
 }
 
-fn find_model_file(model_name: &str, language: Option<&str>) -> Option<PathBuf> {
+fn model_file_names(model_name: &str, language: Option<&str>, multilingual: bool) -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Some(lang) = language {
+        if !(multilingual && lang == "en") {
+            names.push(format!("ggml-{}.{}.bin", model_name, lang));
+        }
+    }
+    if !multilingual {
+        names.push(format!("ggml-{}.en.bin", model_name));
+    }
+    names.push(format!("ggml-{}.bin", model_name));
+    names.dedup();
+
+    names
+}
+
+fn find_model_file(
+    model_name: &str,
+    language: Option<&str>,
+    multilingual: bool,
+) -> Option<PathBuf> {
     let search_dirs = get_model_search_dirs();
+    let file_names = model_file_names(model_name, language, multilingual);
 
     for models_dir in &search_dirs {
         if !models_dir.exists() {
             continue;
         }
 
-        let model_file = if let Some(lang) = language {
-            models_dir.join(format!("ggml-{}.{}.bin", model_name, lang))
-        } else {
-            models_dir.join(format!("ggml-{}.en.bin", model_name))
-        };
-
-        if model_file.exists() {
-            return Some(model_file);
+        for file_name in &file_names {
+            let model_file = models_dir.join(file_name);
+            if model_file.exists() {
+                return Some(model_file);
+            }
         }
+    }
 
-        let model_file_en = models_dir.join(format!("ggml-{}.en.bin", model_name));
-        if model_file_en.exists() {
-            return Some(model_file_en);
-        }
+    None
+}
 
-        let model_file_base = models_dir.join(format!("ggml-{}.bin", model_name));
-        if model_file_base.exists() {
-            return Some(model_file_base);
-        }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledModel {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+fn model_name_from_file(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_prefix("ggml-")?
+        .strip_suffix(".bin")
+        .filter(|name| !name.is_empty())
+}
+
+fn models_in_dir(models_dir: &Path) -> Vec<InstalledModel> {
+    let Ok(entries) = fs::read_dir(models_dir) else {
+        return Vec::new();
+    };
+
+    let mut models: Vec<InstalledModel> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            let file_name = entry.file_name();
+            let name = model_name_from_file(file_name.to_str()?)?;
+            metadata.is_file().then(|| InstalledModel {
+                name: name.to_string(),
+                path: entry.path(),
+                size: metadata.len(),
+            })
+        })
+        .collect();
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+
+    models
+}
+
+/// Every `ggml-*.bin` file in the search directories, in search order.
+pub fn list_installed_models() -> Vec<InstalledModel> {
+    get_model_search_dirs()
+        .iter()
+        .flat_map(|models_dir| models_in_dir(models_dir))
+        .collect()
+}
+
+/// All installed copies of a model, in search order. The first one is the
+/// copy that gets loaded.
+pub fn find_installed_models(model_name: &str) -> Vec<PathBuf> {
+    let file_name = format!("ggml-{}.bin", model_name);
+    get_model_search_dirs()
+        .iter()
+        .map(|models_dir| models_dir.join(&file_name))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Returns the English-only `.en` variant of a model if one is installed.
+pub fn find_english_only_model(model_name: Option<&str>) -> Option<PathBuf> {
+    let model_name = model_name.unwrap_or(DEFAULT_MODEL);
+    if is_model_path(model_name) {
+        return None;
     }
+    find_model_file(model_name, Some("en"), false)
+        .filter(|path| path.to_string_lossy().ends_with(".en.bin"))
+}
 
-    None
+/// Whether `--model` names a file rather than a model: anything that exists
+/// on disk, contains a path separator or ends in `.bin`.
+fn is_model_path(model: &str) -> bool {
+    let path = Path::new(model);
+    path.is_file() || path.components().count() > 1 || model.ends_with(".bin")
 }
 
-pub fn load_model(model_name: Option<&str>, language: Option<&str>) -> Result<WhisperContext> {
+/// The model file when `model` is a path rather than a model name. A path
+/// that does not exist is `ModelNotFound` instead of a download attempt.
+pub fn explicit_model_path(model: &str) -> Result<Option<PathBuf>> {
+    if !is_model_path(model) {
+        return Ok(None);
+    }
+
+    let path = Path::new(model);
+    if !path.is_file() {
+        log_error!("Model file {} does not exist", path.display());
+        return Err(WhisperError::ModelNotFound);
+    }
+    Ok(Some(path.to_path_buf()))
+}
+
+/// Finds a model on disk, downloading it when missing. With `multilingual`
+/// set, English-only `.en` variants are skipped so the model can translate
+/// and detect languages.
+pub fn resolve_model_path(
+    model_name: Option<&str>,
+    language: Option<&str>,
+    multilingual: bool,
+) -> Result<PathBuf> {
     let model_name = model_name.unwrap_or(DEFAULT_MODEL);
+    if let Some(model_path) = explicit_model_path(model_name)? {
+        return Ok(model_path);
+    }
+
     let models_dir = get_models_dir()?;
 
-    if let Some(model_path) = find_model_file(model_name, language) {
-        log_info!("Loading model {}...", model_name);
-        let context = WhisperContext::new_with_params(
-            &model_path.to_string_lossy(),
-            whisper_rs::WhisperContextParameters::default(),
-        )?;
-        log_info!("Model loaded");
-        return Ok(context);
+    if let Some(model_path) = find_model_file(model_name, language, multilingual) {
+        return Ok(model_path);
     }
 
     let model_file_base = models_dir.join(format!("ggml-{}.bin", model_name));
+    ensure_download_allowed(model_name)?;
 
     let silent = crate::output::logger::is_silent();
     if !silent {
-        log_warning!("Model not found in any search location, downloading to ~/.local/share/whisper...");
+        log_warning!(
+            "Model not found in any search location, downloading to {}...",
+            models_dir.display()
+        );
     }
     download_model(model_name, &model_file_base)?;
 
-    log_info!("Loading model {}...", model_name);
+    Ok(model_file_base)
+}
+
+/// Loads a model, first checking its SHA-256 when `verify` is set.
+pub fn load_model_from_path(model_path: &Path, verify: bool) -> Result<WhisperContext> {
+    if verify {
+        verify_model(model_path)?;
+    }
+
+    log_info!("Loading model {}...", model_path.display());
     let context = WhisperContext::new_with_params(
-        &model_file_base.to_string_lossy(),
+        &model_path.to_string_lossy(),
         whisper_rs::WhisperContextParameters::default(),
     )?;
     log_info!("Model loaded");
@@ -98,6 +249,15 @@ pub fn load_model(model_name: Option<&str>, language: Option<&str>) -> Result<Wh
     Ok(context)
 }
 
+pub fn load_model(
+    model_name: Option<&str>,
+    language: Option<&str>,
+    verify: bool,
+) -> Result<WhisperContext> {
+    let model_path = resolve_model_path(model_name, language, false)?;
+    load_model_from_path(&model_path, verify)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,11 +269,17 @@ mod tests {
 
     #[test]
     fn test_get_models_dir() {
-        let result = get_models_dir();
+        let result = models_dir(None);
         assert!(result.is_ok());
         let dir = result.unwrap();
         assert!(dir.ends_with(".local/share/whisper"));
         assert!(dir.to_string_lossy().contains(".local"));
+
+        assert!(get_models_dir().unwrap().is_dir());
+        assert_eq!(
+            models_dir(Some(PathBuf::from("/data/models"))).unwrap(),
+            PathBuf::from("/data/models")
+        );
     }
 
     #[test]
@@ -135,6 +301,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_model_file_names_without_language() {
+        assert_eq!(
+            model_file_names("base", None, false),
+            vec!["ggml-base.en.bin", "ggml-base.bin"]
+        );
+    }
+
+    #[test]
+    fn test_model_file_names_with_language() {
+        assert_eq!(
+            model_file_names("base", Some("es"), false),
+            vec!["ggml-base.es.bin", "ggml-base.en.bin", "ggml-base.bin"]
+        );
+        assert_eq!(
+            model_file_names("base", Some("en"), false),
+            vec!["ggml-base.en.bin", "ggml-base.bin"]
+        );
+    }
+
+    #[test]
+    fn test_model_file_names_multilingual() {
+        assert_eq!(model_file_names("base", None, true), vec!["ggml-base.bin"]);
+        assert_eq!(
+            model_file_names("small", Some("de"), true),
+            vec!["ggml-small.de.bin", "ggml-small.bin"]
+        );
+        assert_eq!(
+            model_file_names("small", Some("en"), true),
+            vec!["ggml-small.bin"]
+        );
+    }
+
     #[test]
     fn test_model_priority_resolution() {
         let base_file = "ggml-base.bin";
@@ -156,6 +355,90 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_model_name_from_file() {
+        assert_eq!(model_name_from_file("ggml-base.en.bin"), Some("base.en"));
+        assert_eq!(
+            model_name_from_file("ggml-large-v3-q5_0.bin"),
+            Some("large-v3-q5_0")
+        );
+        assert_eq!(model_name_from_file("ggml-base.bin.part"), None);
+        assert_eq!(model_name_from_file("ggml-.bin"), None);
+        assert_eq!(model_name_from_file("checksums.sha256"), None);
+    }
+
+    #[test]
+    fn test_models_in_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("ggml-small.bin"), [0u8; 3]).unwrap();
+        fs::write(dir.path().join("ggml-base.bin"), [0u8; 5]).unwrap();
+        fs::write(dir.path().join("ggml-tiny.bin.part"), [0u8; 1]).unwrap();
+        fs::create_dir(dir.path().join("ggml-dir.bin")).unwrap();
+
+        let models = models_in_dir(dir.path());
+        assert_eq!(
+            models,
+            vec![
+                InstalledModel {
+                    name: "base".to_string(),
+                    path: dir.path().join("ggml-base.bin"),
+                    size: 5,
+                },
+                InstalledModel {
+                    name: "small".to_string(),
+                    path: dir.path().join("ggml-small.bin"),
+                    size: 3,
+                },
+            ]
+        );
+        assert!(models_in_dir(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_search_dirs_from_env() {
+        let model_path = env::join_paths(["/opt/models", "/srv/whisper"]).unwrap();
+        let dirs = search_dirs(Some(model_path), Some(PathBuf::from("/data/models")));
+
+        assert_eq!(
+            dirs[..3],
+            [
+                PathBuf::from("/opt/models"),
+                PathBuf::from("/srv/whisper"),
+                PathBuf::from("/data/models"),
+            ]
+        );
+        assert_eq!(dirs.last(), Some(&PathBuf::from("./models")));
+        assert_eq!(dirs.len(), search_dirs(None, None).len() + 3);
+    }
+
+    #[test]
+    fn test_explicit_model_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let model_path = dir.path().join("fine-tuned.bin");
+        fs::write(&model_path, b"ggml").unwrap();
+        let model = model_path.to_string_lossy();
+
+        assert_eq!(
+            explicit_model_path(&model).unwrap(),
+            Some(model_path.clone())
+        );
+        assert_eq!(
+            resolve_model_path(Some(&model), Some("de"), true).unwrap(),
+            model_path
+        );
+        assert_eq!(find_english_only_model(Some(&model)), None);
+
+        assert_eq!(explicit_model_path("base.en").unwrap(), None);
+        assert!(matches!(
+            explicit_model_path("models/missing.bin"),
+            Err(WhisperError::ModelNotFound)
+        ));
+        assert!(matches!(
+            explicit_model_path("ggml-missing.bin"),
+            Err(WhisperError::ModelNotFound)
+        ));
+    }
+
     #[test]
     fn test_model_search_dirs() {
         let dirs = get_model_search_dirs();
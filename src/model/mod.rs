@@ -1,4 +1,12 @@
+pub mod checksum;
 pub mod downloader;
+pub mod header;
 pub mod loader;
 
-pub use loader::{get_models_dir, load_model, DEFAULT_MODEL};
+pub use checksum::{known_models, verify_model};
+pub use header::{read_model_header, ModelHeader};
+pub use loader::{
+    explicit_model_path, find_english_only_model, find_installed_models, get_model_search_dirs,
+    get_models_dir, list_installed_models, load_model, load_model_from_path, resolve_model_path,
+    InstalledModel, DEFAULT_MODEL,
+};
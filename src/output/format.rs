@@ -0,0 +1,131 @@
+use crate::error::Result;
+use crate::output::json::format_json;
+use crate::output::srt::format_srt;
+use crate::output::transcript::Transcript;
+use crate::output::vtt::{format_vtt, CueSettings};
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Srt,
+    Vtt,
+    Json,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    pub cue_settings: CueSettings,
+    pub words_per_cue: Option<usize>,
+}
+
+impl OutputFormat {
+    pub fn render(&self, transcript: &Transcript, options: &RenderOptions) -> Result<String> {
+        let cues = match options.words_per_cue {
+            Some(words_per_cue) => Cow::Owned(transcript.to_word_cues(words_per_cue)),
+            None => Cow::Borrowed(transcript),
+        };
+
+        match self {
+            OutputFormat::Text => Ok(transcript.text()),
+            OutputFormat::Srt => Ok(format_srt(&cues)),
+            OutputFormat::Vtt => Ok(format_vtt(&cues, &options.cue_settings)),
+            OutputFormat::Json => format_json(transcript),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::transcript::{Segment, Word};
+
+    fn sample_transcript() -> Transcript {
+        Transcript {
+            segments: vec![
+                Segment {
+                    start_ms: 0,
+                    end_ms: 1_000,
+                    text: "One".to_string(),
+                    ..Default::default()
+                },
+                Segment {
+                    start_ms: 1_000,
+                    end_ms: 2_000,
+                    text: "two".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_default_output_format() {
+        assert_eq!(OutputFormat::default(), OutputFormat::Text);
+    }
+
+    #[test]
+    fn test_render_text() {
+        let output = OutputFormat::Text
+            .render(&sample_transcript(), &RenderOptions::default())
+            .unwrap();
+        assert_eq!(output, "One two");
+    }
+
+    #[test]
+    fn test_render_srt() {
+        let output = OutputFormat::Srt
+            .render(&sample_transcript(), &RenderOptions::default())
+            .unwrap();
+        assert!(output.starts_with("1\n00:00:00,000 --> 00:00:01,000\nOne\n"));
+        assert!(output.contains("2\n00:00:01,000 --> 00:00:02,000\ntwo\n"));
+    }
+
+    #[test]
+    fn test_render_vtt() {
+        let output = OutputFormat::Vtt
+            .render(&sample_transcript(), &RenderOptions::default())
+            .unwrap();
+        assert!(output.starts_with("WEBVTT\n\n"));
+        assert!(output.contains("00:00:01.000 --> 00:00:02.000\ntwo\n"));
+    }
+
+    #[test]
+    fn test_render_srt_word_cues() {
+        let mut transcript = sample_transcript();
+        transcript.segments[0].words = vec![
+            Word {
+                text: "One".to_string(),
+                start_ms: 0,
+                end_ms: 400,
+                probability: 1.0,
+            },
+            Word {
+                text: "more".to_string(),
+                start_ms: 500,
+                end_ms: 1_000,
+                probability: 1.0,
+            },
+        ];
+        let options = RenderOptions {
+            words_per_cue: Some(1),
+            ..Default::default()
+        };
+
+        let output = OutputFormat::Srt.render(&transcript, &options).unwrap();
+        assert!(output.contains("1\n00:00:00,000 --> 00:00:00,400\nOne\n"));
+        assert!(output.contains("2\n00:00:00,500 --> 00:00:01,000\nmore\n"));
+        assert!(output.contains("3\n00:00:01,000 --> 00:00:02,000\ntwo\n"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let output = OutputFormat::Json
+            .render(&sample_transcript(), &RenderOptions::default())
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["segments"][1]["text"], "two");
+    }
+}
@@ -0,0 +1,100 @@
+use crate::error::Result;
+use crate::output::transcript::Transcript;
+
+pub fn format_json(transcript: &Transcript) -> Result<String> {
+    Ok(serde_json::to_string_pretty(transcript)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::transcript::{Segment, Token, Word};
+
+    fn sample_transcript() -> Transcript {
+        Transcript {
+            source_file: Some("call.wav".to_string()),
+            model: Some("base".to_string()),
+            model_path: Some("/models/ggml-base.bin".to_string()),
+            language: Some("en".to_string()),
+            language_probability: Some(0.75),
+            duration_ms: Some(3_000),
+            segments: vec![Segment {
+                start_ms: 0,
+                end_ms: 1_500,
+                text: "Hi".to_string(),
+                speaker: None,
+                avg_probability: 0.5,
+                tokens: vec![Token {
+                    text: " Hi".to_string(),
+                    probability: 0.5,
+                    start_ms: 0,
+                    end_ms: 1_500,
+                }],
+                words: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_format_json_document() {
+        let output = format_json(&sample_transcript()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(value["source_file"], "call.wav");
+        assert_eq!(value["model"], "base");
+        assert_eq!(value["model_path"], "/models/ggml-base.bin");
+        assert_eq!(value["language"], "en");
+        assert_eq!(value["language_probability"], 0.75);
+        assert_eq!(value["duration_ms"], 3_000);
+
+        let segment = &value["segments"][0];
+        assert_eq!(segment["start_ms"], 0);
+        assert_eq!(segment["end_ms"], 1_500);
+        assert_eq!(segment["text"], "Hi");
+        assert_eq!(segment["avg_probability"], 0.5);
+        assert_eq!(segment["tokens"][0]["text"], " Hi");
+        assert_eq!(segment["tokens"][0]["probability"], 0.5);
+        assert_eq!(segment["tokens"][0]["end_ms"], 1_500);
+    }
+
+    #[test]
+    fn test_format_json_words() {
+        let mut transcript = sample_transcript();
+        let output = format_json(&transcript).unwrap();
+        assert!(!output.contains("\"words\""));
+
+        transcript.segments[0].words = vec![Word {
+            text: "Hi".to_string(),
+            start_ms: 100,
+            end_ms: 600,
+            probability: 0.5,
+        }];
+        let output = format_json(&transcript).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let word = &value["segments"][0]["words"][0];
+        assert_eq!(word["text"], "Hi");
+        assert_eq!(word["start_ms"], 100);
+        assert_eq!(word["end_ms"], 600);
+    }
+
+    #[test]
+    fn test_format_json_speaker() {
+        let mut transcript = sample_transcript();
+        let output = format_json(&transcript).unwrap();
+        assert!(!output.contains("\"speaker\""));
+
+        transcript.segments[0].speaker = Some("Customer".to_string());
+        let output = format_json(&transcript).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["segments"][0]["speaker"], "Customer");
+    }
+
+    #[test]
+    fn test_format_json_empty_transcript() {
+        let output = format_json(&Transcript::default()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert!(value["language"].is_null());
+        assert!(value.get("language_probability").is_none());
+        assert_eq!(value["segments"].as_array().unwrap().len(), 0);
+    }
+}
@@ -1,3 +1,12 @@
+pub mod format;
+pub mod json;
 pub mod logger;
+pub mod srt;
+pub mod transcript;
+pub mod vtt;
+pub mod writer;
 
+pub use format::{OutputFormat, RenderOptions};
 pub use logger::{is_silent, log, set_silent, LogLevel};
+pub use transcript::{Segment, Token, Transcript, Word};
+pub use writer::write_output;
@@ -0,0 +1,90 @@
+use crate::output::transcript::{format_clock, Transcript};
+
+pub fn format_timestamp(ms: i64) -> String {
+    format_clock(ms, ',')
+}
+
+pub fn format_srt(transcript: &Transcript) -> String {
+    let mut output = String::new();
+
+    for (index, segment) in transcript.segments.iter().enumerate() {
+        output.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(segment.start_ms),
+            format_timestamp(segment.end_ms),
+            segment.labeled_text()
+        ));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::transcript::Segment;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "00:00:00,000");
+        assert_eq!(format_timestamp(1_500), "00:00:01,500");
+        assert_eq!(format_timestamp(61_001), "00:01:01,001");
+        assert_eq!(format_timestamp(3_723_456), "01:02:03,456");
+    }
+
+    #[test]
+    fn test_format_timestamp_negative_clamped() {
+        assert_eq!(format_timestamp(-10), "00:00:00,000");
+    }
+
+    #[test]
+    fn test_format_srt() {
+        let transcript = Transcript {
+            segments: vec![
+                Segment {
+                    start_ms: 0,
+                    end_ms: 2_500,
+                    text: "Hello there.".to_string(),
+                    ..Default::default()
+                },
+                Segment {
+                    start_ms: 2_500,
+                    end_ms: 4_000,
+                    text: "General Kenobi.".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            format_srt(&transcript),
+            "1\n00:00:00,000 --> 00:00:02,500\nHello there.\n\n\
+             2\n00:00:02,500 --> 00:00:04,000\nGeneral Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_srt_with_speaker() {
+        let transcript = Transcript {
+            segments: vec![Segment {
+                start_ms: 0,
+                end_ms: 1_000,
+                text: "Thanks for calling.".to_string(),
+                speaker: Some("CH1".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            format_srt(&transcript),
+            "1\n00:00:00,000 --> 00:00:01,000\n[CH1] Thanks for calling.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_srt_empty() {
+        assert_eq!(format_srt(&Transcript::default()), "");
+    }
+}
@@ -0,0 +1,405 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Token {
+    pub text: String,
+    pub probability: f32,
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Word {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub probability: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Segment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+    /// Channel label when channels are transcribed separately.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    pub avg_probability: f32,
+    pub tokens: Vec<Token>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Transcript {
+    pub source_file: Option<String>,
+    pub model: Option<String>,
+    pub model_path: Option<String>,
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_probability: Option<f32>,
+    pub duration_ms: Option<i64>,
+    pub segments: Vec<Segment>,
+}
+
+impl Segment {
+    /// Rewrites every timestamp in the segment, its tokens and its words.
+    pub fn map_times(&mut self, map: impl Fn(i64) -> i64) {
+        self.start_ms = map(self.start_ms);
+        self.end_ms = map(self.end_ms);
+        for token in &mut self.tokens {
+            token.start_ms = map(token.start_ms);
+            token.end_ms = map(token.end_ms);
+        }
+        for word in &mut self.words {
+            word.start_ms = map(word.start_ms);
+            word.end_ms = map(word.end_ms);
+        }
+    }
+
+    /// The segment text prefixed with its speaker label, if any.
+    pub fn labeled_text(&self) -> String {
+        match &self.speaker {
+            Some(speaker) => format!("[{}] {}", speaker, self.text),
+            None => self.text.clone(),
+        }
+    }
+}
+
+impl Transcript {
+    /// Plain text of the transcript. With speaker labels, each change of
+    /// speaker starts a new labeled line.
+    pub fn text(&self) -> String {
+        if self
+            .segments
+            .iter()
+            .all(|segment| segment.speaker.is_none())
+        {
+            return self
+                .segments
+                .iter()
+                .map(|segment| segment.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut current_speaker = None;
+        for segment in &self.segments {
+            match lines.last_mut() {
+                Some(line) if current_speaker == Some(&segment.speaker) => {
+                    line.push(' ');
+                    line.push_str(&segment.text);
+                }
+                _ => {
+                    lines.push(segment.labeled_text());
+                    current_speaker = Some(&segment.speaker);
+                }
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Regroups word-level timings into cues of `words_per_cue` words each.
+    /// Segments without word timings are kept as they are.
+    pub fn to_word_cues(&self, words_per_cue: usize) -> Transcript {
+        let words_per_cue = words_per_cue.max(1);
+        let mut segments = Vec::new();
+
+        for segment in &self.segments {
+            if segment.words.is_empty() {
+                segments.push(segment.clone());
+                continue;
+            }
+
+            for words in segment.words.chunks(words_per_cue) {
+                let probabilities = words.iter().map(|word| word.probability);
+                segments.push(Segment {
+                    start_ms: words[0].start_ms,
+                    end_ms: words[words.len() - 1].end_ms,
+                    text: words
+                        .iter()
+                        .map(|word| word.text.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    speaker: segment.speaker.clone(),
+                    avg_probability: probabilities.sum::<f32>() / words.len() as f32,
+                    tokens: Vec::new(),
+                    words: words.to_vec(),
+                });
+            }
+        }
+
+        Transcript {
+            segments,
+            ..self.clone()
+        }
+    }
+}
+
+/// Merges per-channel segments into one timeline, ordered by start time.
+/// Segments starting together keep their channel order.
+pub fn interleave_segments(channels: Vec<Vec<Segment>>) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = channels.into_iter().flatten().collect();
+    segments.sort_by_key(|segment| segment.start_ms);
+    segments
+}
+
+pub fn is_blank_segment(text: &str) -> bool {
+    text.is_empty() || text == "[BLANK_AUDIO]"
+}
+
+pub fn average_probability(tokens: &[Token]) -> f32 {
+    if tokens.is_empty() {
+        return 0.0;
+    }
+    tokens.iter().map(|token| token.probability).sum::<f32>() / tokens.len() as f32
+}
+
+/// Reassembles whole words from whisper's subword tokens. A token that starts
+/// with a space begins a new word; anything else (word pieces, punctuation)
+/// is glued onto the previous one.
+pub fn words_from_tokens(tokens: &[Token]) -> Vec<Word> {
+    let mut words: Vec<Word> = Vec::new();
+    let mut token_counts: Vec<usize> = Vec::new();
+
+    for token in tokens {
+        let trimmed = token.text.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let starts_word = token.text.starts_with(char::is_whitespace);
+        match words.last_mut() {
+            Some(word) if !starts_word => {
+                word.text.push_str(trimmed);
+                word.end_ms = word.end_ms.max(token.end_ms);
+                word.probability += token.probability;
+                if let Some(count) = token_counts.last_mut() {
+                    *count += 1;
+                }
+            }
+            _ => {
+                words.push(Word {
+                    text: trimmed.to_string(),
+                    start_ms: token.start_ms,
+                    end_ms: token.end_ms,
+                    probability: token.probability,
+                });
+                token_counts.push(1);
+            }
+        }
+    }
+
+    for (word, count) in words.iter_mut().zip(token_counts) {
+        word.probability /= count as f32;
+    }
+
+    words
+}
+
+pub fn format_clock(ms: i64, decimal_marker: char) -> String {
+    let ms = ms.max(0);
+    let hours = ms / 3_600_000;
+    let minutes = (ms % 3_600_000) / 60_000;
+    let seconds = (ms % 60_000) / 1000;
+    let millis = ms % 1000;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        hours, minutes, seconds, decimal_marker, millis
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: i64, end_ms: i64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn token(probability: f32) -> Token {
+        Token {
+            probability,
+            ..Default::default()
+        }
+    }
+
+    fn timed_token(text: &str, start_ms: i64, end_ms: i64, probability: f32) -> Token {
+        Token {
+            text: text.to_string(),
+            probability,
+            start_ms,
+            end_ms,
+        }
+    }
+
+    fn word(text: &str, start_ms: i64, end_ms: i64) -> Word {
+        Word {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            probability: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_transcript_text_joins_segments() {
+        let transcript = Transcript {
+            segments: vec![segment(0, 1000, "Hello"), segment(1000, 2000, "world.")],
+            ..Default::default()
+        };
+        assert_eq!(transcript.text(), "Hello world.");
+    }
+
+    #[test]
+    fn test_transcript_text_with_speakers() {
+        let labeled = |start_ms, text, speaker: &str| Segment {
+            speaker: Some(speaker.to_string()),
+            ..segment(start_ms, start_ms + 500, text)
+        };
+        let transcript = Transcript {
+            segments: vec![
+                labeled(0, "Hello,", "Agent"),
+                labeled(500, "how can I help?", "Agent"),
+                labeled(1000, "Hi.", "Customer"),
+                labeled(1500, "Sure.", "Agent"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            transcript.text(),
+            "[Agent] Hello, how can I help?\n[Customer] Hi.\n[Agent] Sure."
+        );
+    }
+
+    #[test]
+    fn test_interleave_segments() {
+        let left = vec![segment(0, 1000, "a"), segment(3000, 4000, "c")];
+        let right = vec![segment(1500, 2000, "b"), segment(3000, 3500, "d")];
+        let merged = interleave_segments(vec![left, right]);
+        let texts: Vec<&str> = merged.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_segment_map_times() {
+        let mut segment = Segment {
+            tokens: vec![timed_token(" Hi", 100, 200, 1.0)],
+            words: vec![word("Hi", 100, 200)],
+            ..segment(0, 300, "Hi")
+        };
+        segment.map_times(|ms| ms + 5_000);
+
+        assert_eq!((segment.start_ms, segment.end_ms), (5_000, 5_300));
+        assert_eq!(
+            (segment.tokens[0].start_ms, segment.tokens[0].end_ms),
+            (5_100, 5_200)
+        );
+        assert_eq!(
+            (segment.words[0].start_ms, segment.words[0].end_ms),
+            (5_100, 5_200)
+        );
+    }
+
+    #[test]
+    fn test_labeled_text() {
+        assert_eq!(segment(0, 1, "Hi").labeled_text(), "Hi");
+        let labeled = Segment {
+            speaker: Some("CH2".to_string()),
+            ..segment(0, 1, "Hi")
+        };
+        assert_eq!(labeled.labeled_text(), "[CH2] Hi");
+    }
+
+    #[test]
+    fn test_transcript_text_empty() {
+        assert_eq!(Transcript::default().text(), "");
+    }
+
+    #[test]
+    fn test_is_blank_segment() {
+        assert!(is_blank_segment(""));
+        assert!(is_blank_segment("[BLANK_AUDIO]"));
+        assert!(!is_blank_segment("Hello"));
+    }
+
+    #[test]
+    fn test_average_probability() {
+        assert_eq!(average_probability(&[]), 0.0);
+        assert_eq!(average_probability(&[token(0.5), token(1.0)]), 0.75);
+    }
+
+    #[test]
+    fn test_words_from_tokens() {
+        let tokens = vec![
+            timed_token(" Hello", 0, 400, 0.9),
+            timed_token(" trans", 500, 700, 0.8),
+            timed_token("cription", 700, 1_100, 0.6),
+            timed_token(".", 1_100, 1_200, 1.0),
+        ];
+
+        let words = words_from_tokens(&tokens);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello");
+        assert_eq!((words[0].start_ms, words[0].end_ms), (0, 400));
+        assert_eq!(words[1].text, "transcription.");
+        assert_eq!((words[1].start_ms, words[1].end_ms), (500, 1_200));
+        assert!((words[1].probability - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_words_from_tokens_first_token_without_space() {
+        let tokens = vec![
+            timed_token("Hi", 0, 100, 1.0),
+            timed_token(" ", 100, 120, 1.0),
+        ];
+        let words = words_from_tokens(&tokens);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].text, "Hi");
+    }
+
+    #[test]
+    fn test_to_word_cues() {
+        let transcript = Transcript {
+            segments: vec![
+                Segment {
+                    words: vec![
+                        word("one", 0, 100),
+                        word("two", 100, 200),
+                        word("three", 200, 300),
+                    ],
+                    ..segment(0, 300, "one two three")
+                },
+                segment(300, 400, "untimed"),
+            ],
+            ..Default::default()
+        };
+
+        let mut transcript = transcript;
+        transcript.segments[0].speaker = Some("CH1".to_string());
+
+        let cues = transcript.to_word_cues(2);
+        assert_eq!(cues.segments.len(), 3);
+        assert_eq!(cues.segments[1].speaker.as_deref(), Some("CH1"));
+        assert_eq!(cues.segments[0].text, "one two");
+        assert_eq!(
+            (cues.segments[0].start_ms, cues.segments[0].end_ms),
+            (0, 200)
+        );
+        assert_eq!(cues.segments[1].text, "three");
+        assert_eq!(cues.segments[2].text, "untimed");
+    }
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(3_723_456, ','), "01:02:03,456");
+        assert_eq!(format_clock(3_723_456, '.'), "01:02:03.456");
+        assert_eq!(format_clock(-5, '.'), "00:00:00.000");
+    }
+}
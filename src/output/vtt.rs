@@ -0,0 +1,272 @@
+use crate::output::transcript::{format_clock, Transcript};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CueAlign {
+    Start,
+    Center,
+    End,
+    Left,
+    Right,
+}
+
+impl CueAlign {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CueAlign::Start => "start",
+            CueAlign::Center => "center",
+            CueAlign::End => "end",
+            CueAlign::Left => "left",
+            CueAlign::Right => "right",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CueSettings {
+    pub line: Option<String>,
+    pub position: Option<String>,
+    pub align: Option<CueAlign>,
+}
+
+impl CueSettings {
+    pub fn is_empty(&self) -> bool {
+        self.line.is_none() && self.position.is_none() && self.align.is_none()
+    }
+
+    fn render(&self) -> String {
+        let mut settings = Vec::new();
+        if let Some(line) = &self.line {
+            settings.push(format!("line:{}", line));
+        }
+        if let Some(position) = &self.position {
+            settings.push(format!("position:{}", position));
+        }
+        if let Some(align) = &self.align {
+            settings.push(format!("align:{}", align.as_str()));
+        }
+        settings.join(" ")
+    }
+}
+
+pub fn format_timestamp(ms: i64) -> String {
+    format_clock(ms, '.')
+}
+
+fn is_percentage(value: &str) -> bool {
+    let Some(number) = value.strip_suffix('%') else {
+        return false;
+    };
+    matches!(number.parse::<f32>(), Ok(n) if (0.0..=100.0).contains(&n))
+}
+
+/// Accepts a WebVTT `line` value: a line number or a percentage, optionally
+/// followed by `,start`, `,center` or `,end`.
+pub fn parse_line_setting(value: &str) -> Result<String, String> {
+    let (line, anchor) = match value.split_once(',') {
+        Some((line, anchor)) => (line, Some(anchor)),
+        None => (value, None),
+    };
+
+    let line_valid = is_percentage(line) || line.parse::<i32>().is_ok();
+    let anchor_valid = anchor.is_none_or(|a| matches!(a, "start" | "center" | "end"));
+
+    if line_valid && anchor_valid {
+        Ok(value.to_string())
+    } else {
+        Err(format!(
+            "invalid cue line '{}': expected a line number or percentage, optionally followed by ,start|,center|,end",
+            value
+        ))
+    }
+}
+
+/// Accepts a WebVTT `position` value: a percentage, optionally followed by
+/// `,line-left`, `,center` or `,line-right`.
+pub fn parse_position_setting(value: &str) -> Result<String, String> {
+    let (position, anchor) = match value.split_once(',') {
+        Some((position, anchor)) => (position, Some(anchor)),
+        None => (value, None),
+    };
+
+    let anchor_valid = anchor.is_none_or(|a| matches!(a, "line-left" | "center" | "line-right"));
+
+    if is_percentage(position) && anchor_valid {
+        Ok(value.to_string())
+    } else {
+        Err(format!(
+            "invalid cue position '{}': expected a percentage, optionally followed by ,line-left|,center|,line-right",
+            value
+        ))
+    }
+}
+
+// NOTE blocks end at the first blank line and may not contain "-->".
+fn sanitize_note(value: &str) -> String {
+    value
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("-->", "->")
+}
+
+// Cue text and voice span annotations may not contain raw markup. Escaping
+// '>' also keeps "-->" out of the cue payload.
+fn escape_cue_text(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn format_vtt(transcript: &Transcript, cue_settings: &CueSettings) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+
+    let metadata = [
+        ("model", &transcript.model),
+        ("language", &transcript.language),
+        ("source", &transcript.source_file),
+    ];
+    if metadata.iter().any(|(_, value)| value.is_some()) {
+        output.push_str("NOTE\n");
+        for (key, value) in metadata {
+            if let Some(value) = value {
+                output.push_str(&format!("{}: {}\n", key, sanitize_note(value)));
+            }
+        }
+        output.push('\n');
+    }
+
+    let settings = cue_settings.render();
+
+    for segment in &transcript.segments {
+        output.push_str(&format_timestamp(segment.start_ms));
+        output.push_str(" --> ");
+        output.push_str(&format_timestamp(segment.end_ms));
+        if !settings.is_empty() {
+            output.push(' ');
+            output.push_str(&settings);
+        }
+        output.push('\n');
+        if let Some(speaker) = &segment.speaker {
+            output.push_str(&format!("<v {}>", escape_cue_text(speaker)));
+        }
+        output.push_str(&escape_cue_text(&segment.text));
+        output.push_str("\n\n");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::transcript::Segment;
+
+    fn sample_transcript() -> Transcript {
+        Transcript {
+            source_file: Some("meeting.mp3".to_string()),
+            model: Some("base".to_string()),
+            language: Some("en".to_string()),
+            segments: vec![Segment {
+                start_ms: 1_250,
+                end_ms: 3_000,
+                text: "Welcome everyone.".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "00:00:00.000");
+        assert_eq!(format_timestamp(3_723_456), "01:02:03.456");
+    }
+
+    #[test]
+    fn test_format_vtt_with_metadata() {
+        let output = format_vtt(&sample_transcript(), &CueSettings::default());
+        assert_eq!(
+            output,
+            "WEBVTT\n\n\
+             NOTE\nmodel: base\nlanguage: en\nsource: meeting.mp3\n\n\
+             00:00:01.250 --> 00:00:03.000\nWelcome everyone.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_vtt_without_metadata() {
+        let transcript = Transcript {
+            segments: sample_transcript().segments,
+            ..Default::default()
+        };
+        let output = format_vtt(&transcript, &CueSettings::default());
+        assert!(output.starts_with("WEBVTT\n\n00:00:01.250"));
+        assert!(!output.contains("NOTE"));
+    }
+
+    #[test]
+    fn test_format_vtt_with_cue_settings() {
+        let settings = CueSettings {
+            line: Some("90%".to_string()),
+            position: Some("50%".to_string()),
+            align: Some(CueAlign::Center),
+        };
+        let output = format_vtt(&sample_transcript(), &settings);
+        assert!(
+            output.contains("00:00:01.250 --> 00:00:03.000 line:90% position:50% align:center\n")
+        );
+    }
+
+    #[test]
+    fn test_format_vtt_with_speaker() {
+        let mut transcript = sample_transcript();
+        transcript.segments[0].speaker = Some("Agent <1>".to_string());
+        let output = format_vtt(&transcript, &CueSettings::default());
+        assert!(output.contains("00:00:03.000\n<v Agent &lt;1&gt;>Welcome everyone.\n"));
+    }
+
+    #[test]
+    fn test_format_vtt_escapes_cue_text() {
+        let mut transcript = sample_transcript();
+        transcript.segments[0].text = "R&D says x < y --> done".to_string();
+        let output = format_vtt(&transcript, &CueSettings::default());
+        assert!(output.contains("00:00:03.000\nR&amp;D says x &lt; y --&gt; done\n"));
+    }
+
+    #[test]
+    fn test_sanitize_note() {
+        assert_eq!(sanitize_note("a --> b"), "a -> b");
+        assert_eq!(sanitize_note("line one\n\nline two"), "line one  line two");
+    }
+
+    #[test]
+    fn test_parse_line_setting() {
+        assert!(parse_line_setting("0").is_ok());
+        assert!(parse_line_setting("-1").is_ok());
+        assert!(parse_line_setting("85%").is_ok());
+        assert!(parse_line_setting("85%,end").is_ok());
+        assert!(parse_line_setting("150%").is_err());
+        assert!(parse_line_setting("top").is_err());
+        assert!(parse_line_setting("10,middle").is_err());
+    }
+
+    #[test]
+    fn test_parse_position_setting() {
+        assert!(parse_position_setting("50%").is_ok());
+        assert!(parse_position_setting("10%,line-left").is_ok());
+        assert!(parse_position_setting("50").is_err());
+        assert!(parse_position_setting("50%,start").is_err());
+    }
+
+    #[test]
+    fn test_cue_settings_is_empty() {
+        assert!(CueSettings::default().is_empty());
+        let settings = CueSettings {
+            align: Some(CueAlign::Start),
+            ..Default::default()
+        };
+        assert!(!settings.is_empty());
+    }
+}
@@ -0,0 +1,36 @@
+use crate::error::Result;
+use std::fs::File;
+use std::io::Write;
+
+pub fn write_output(output: &str, outfile: Option<&str>) -> Result<()> {
+    if let Some(outfile) = outfile {
+        let mut file = File::create(outfile)?;
+        file.write_all(output.as_bytes())?;
+    } else if output.ends_with('\n') {
+        print!("{}", output);
+    } else {
+        println!("{}", output);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_write_output_to_file() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_string_lossy().to_string();
+        write_output("hello\n", Some(&path)).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello\n");
+    }
+
+    #[test]
+    fn test_write_output_invalid_path() {
+        let result = write_output("hello", Some("/nonexistent/dir/out.txt"));
+        assert!(result.is_err());
+    }
+}
@@ -41,6 +41,22 @@ fn test_cli_help() {
         .stdout(predicate::str::contains("transcribe"));
 }
 
+#[test]
+fn test_cli_help_lists_translate() {
+    let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
+    cmd.arg("--help");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("translate"));
+}
+
+#[test]
+fn test_translate_missing_file() {
+    let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
+    cmd.arg("translate");
+    cmd.assert().failure();
+}
+
 #[test]
 fn test_cli_version() {
     let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
@@ -137,6 +153,27 @@ fn test_unsupported_format_pdf() {
     cmd.assert().failure();
 }
 
+#[test]
+fn test_stdin_unsupported_format() {
+    let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
+    cmd.arg("transcribe")
+        .arg("-")
+        .write_stdin("not an audio file");
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_stdin_wav() {
+    let test_wav = create_test_wav();
+    let data = std::fs::read(test_wav.path()).unwrap();
+
+    let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
+    cmd.arg("transcribe")
+        .arg("-")
+        .write_stdin(data);
+    cmd.assert().success();
+}
+
 #[test]
 fn test_file_not_found() {
     let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
@@ -218,3 +255,39 @@ fn test_no_command() {
     let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
     cmd.assert().failure();
 }
+
+#[test]
+fn test_models_list_info_and_rm() {
+    let home = assert_fs::TempDir::new().unwrap();
+    let model = home.child(".local/share/whisper/ggml-custom.bin");
+    let mut header = Vec::new();
+    for value in [0x6767_6d6c, 51864, 1500, 384, 6, 4, 448, 384, 6, 4, 80, 1] {
+        header.extend_from_slice(&(value as u32).to_le_bytes());
+    }
+    model.write_binary(&header).unwrap();
+
+    let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
+    cmd.env("HOME", home.path()).args(["models", "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("ggml-custom.bin"))
+        .stdout(predicate::str::contains("Available to download"));
+
+    let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
+    cmd.env("HOME", home.path()).args(["models", "info", "custom"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("type:         tiny"))
+        .stdout(predicate::str::contains("multilingual: no"));
+
+    let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
+    cmd.env("HOME", home.path()).args(["models", "rm", "custom"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Removed"));
+    model.assert(predicate::path::missing());
+
+    let mut cmd = Command::cargo_bin("whisper-rs-cli").unwrap();
+    cmd.env("HOME", home.path()).args(["models", "rm", "custom"]);
+    cmd.assert().failure();
+}